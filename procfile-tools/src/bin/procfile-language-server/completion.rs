@@ -0,0 +1,77 @@
+//! Completion of common process types at the start of a line
+use crate::position::{line, offset};
+use lsp_types::{CompletionItem, CompletionItemKind, Position};
+
+const PROCESS_TYPES: &[(&str, &str)] = &[
+    (
+        "web",
+        "Receives HTTP traffic on `$PORT`, the default process when present",
+    ),
+    ("worker", "Processes background jobs"),
+    ("release", "Runs tasks such as database migrations"),
+    ("clock", "Schedules recurring jobs"),
+    ("scheduler", "Schedules recurring jobs"),
+    ("console", "Starts an interactive console"),
+];
+
+pub(crate) fn completions(text: &str, position: Position) -> Vec<CompletionItem> {
+    let cursor = offset(text, position);
+    let line_start = text[..cursor].rfind('\n').map_or(0, |index| index + 1);
+    let typed = &text[line_start..cursor];
+    if typed.contains([':', '#']) {
+        return Vec::new();
+    }
+
+    let defined = text
+        .split('\n')
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, _)| key.trim())
+        .collect::<Vec<_>>();
+    let current = line(text, line_start)
+        .split_once(':')
+        .map(|(key, _)| key.trim());
+
+    PROCESS_TYPES
+        .iter()
+        .filter(|(name, _)| !defined.contains(name) || current == Some(*name))
+        .map(|(name, description)| CompletionItem {
+            label: (*name).to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            detail: Some((*description).to_string()),
+            insert_text: current.is_none().then(|| format!("{name}: ")),
+            ..CompletionItem::default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(text: &str, line: u32, character: u32) -> Vec<String> {
+        completions(text, Position::new(line, character))
+            .into_iter()
+            .map(|item| item.label)
+            .collect()
+    }
+
+    #[test]
+    fn completes_undefined_process_types() {
+        let text = "web: rails s\n\n";
+
+        assert_eq!(
+            vec!["worker", "release", "clock", "scheduler", "console"],
+            labels(text, 1, 0)
+        );
+        assert_eq!(
+            Some(String::from("worker: ")),
+            completions(text, Position::new(1, 0))[0].insert_text
+        );
+    }
+
+    #[test]
+    fn no_completions_in_commands_or_comments() {
+        assert!(labels("web: rails s", 0, 7).is_empty());
+        assert!(labels("# web", 0, 3).is_empty());
+    }
+}
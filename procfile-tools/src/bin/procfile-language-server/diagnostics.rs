@@ -0,0 +1,113 @@
+//! Diagnostics and quick-fixes from the same parser the buildpack uses
+use crate::position::range;
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, DiagnosticSeverity,
+    NumberOrString, Range, TextEdit, Uri, WorkspaceEdit,
+};
+use procfile_buildpack::{Severity, procfile_diagnostics};
+use std::collections::HashMap;
+
+pub(crate) fn diagnostics(text: &str) -> Vec<Diagnostic> {
+    procfile_diagnostics(text)
+        .into_iter()
+        .map(|diagnostic| Diagnostic {
+            range: range(text, &diagnostic.span),
+            code: Some(NumberOrString::String(diagnostic.rule.id.to_string())),
+            severity: Some(match diagnostic.severity {
+                Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warning => DiagnosticSeverity::WARNING,
+            }),
+            source: Some(String::from("procfile")),
+            message: diagnostic.message,
+            ..Diagnostic::default()
+        })
+        .collect()
+}
+
+/// Quick-fixes for the diagnostics overlapping `selection`
+pub(crate) fn code_actions(uri: &Uri, text: &str, selection: Range) -> Vec<CodeActionOrCommand> {
+    procfile_diagnostics(text)
+        .into_iter()
+        .filter_map(|diagnostic| {
+            let fix = diagnostic.fix?;
+            let diagnostic_range = range(text, &diagnostic.span);
+            if diagnostic_range.end < selection.start || selection.end < diagnostic_range.start {
+                return None;
+            }
+
+            let edit = TextEdit {
+                range: range(text, &fix.span),
+                new_text: fix.replacement,
+            };
+            Some(CodeActionOrCommand::CodeAction(CodeAction {
+                title: fix.description,
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![Diagnostic {
+                    range: diagnostic_range,
+                    code: Some(NumberOrString::String(diagnostic.rule.id.to_string())),
+                    severity: Some(DiagnosticSeverity::WARNING),
+                    source: Some(String::from("procfile")),
+                    message: diagnostic.message,
+                    ..Diagnostic::default()
+                }]),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                    ..WorkspaceEdit::default()
+                }),
+                is_preferred: Some(true),
+                ..CodeAction::default()
+            }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::Position;
+
+    #[test]
+    fn corrected_key_quick_fix() {
+        let uri: Uri = "file:///app/Procfile".parse().unwrap();
+        let text = "web: rails s\nWorker_1: sidekiq\n";
+        let actions = code_actions(
+            &uri,
+            text,
+            Range::new(Position::new(1, 2), Position::new(1, 2)),
+        );
+
+        assert_eq!(1, actions.len());
+        let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+            unreachable!("only code actions are returned")
+        };
+        assert_eq!("Rename `Worker_1` to `worker-1`", action.title);
+        assert_eq!(
+            Some(&vec![TextEdit {
+                range: Range::new(Position::new(1, 0), Position::new(1, 8)),
+                new_text: String::from("worker-1"),
+            }]),
+            action
+                .edit
+                .as_ref()
+                .and_then(|edit| edit.changes.as_ref())
+                .and_then(|changes| changes.get(&uri))
+        );
+        assert!(
+            code_actions(
+                &uri,
+                text,
+                Range::new(Position::new(0, 0), Position::new(0, 3))
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn parse_error_diagnostic() {
+        let diagnostics = diagnostics("web: rails s\nweb-: puma\n");
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(Some(DiagnosticSeverity::ERROR), diagnostics[0].severity);
+        assert_eq!(1, diagnostics[0].range.start.line);
+    }
+}
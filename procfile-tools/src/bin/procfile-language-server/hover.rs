@@ -0,0 +1,93 @@
+//! Hover text explaining the rules of `SPEC.md` that apply to the hovered line
+use crate::position::{line, offset, range};
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position};
+use procfile_buildpack::procfile_diagnostics;
+
+const COMMENT: &str = "\
+**Comment**
+
+- Comments MUST contain a `#` as the first visible character on each line.
+- A comment MAY be proceeded by one or more spaces.";
+
+const KEY: &str = "\
+**Process type**
+
+- A key's first and last character MUST be a lowercase alphanumeric (a-z0-9) character (but not `-`).
+- All other key (middle) characters MUST be lowercase alphanumeric (a-z0-9) characters or hyphen `-`.
+- Key length MUST be within the range `1..=63`
+- Duplicate keys MUST be allowed and the last entry MUST take precedence.";
+
+const CORRECTED_KEY: &str = "\
+Keys with `_` as a middle character, uppercase characters or leading spaces are accepted, \
+but corrected with a warning.";
+
+const VALUE: &str = "\
+**Command**
+
+- A value MUST contain 1 or more non-whitespace characters.
+- A value MUST be terminated by a newline or EOF.
+
+The command is run with `bash -c`.";
+
+pub(crate) fn hover(text: &str, position: Position) -> Option<Hover> {
+    let cursor = offset(text, position);
+    let line_start = text[..cursor].rfind('\n').map_or(0, |index| index + 1);
+    let line = line(text, line_start);
+    let column = cursor - line_start;
+
+    let (contents, span) = if line.trim_start().starts_with('#') {
+        (COMMENT.to_string(), line_start..line_start + line.len())
+    } else {
+        let colon = line.find(':')?;
+        if column <= colon {
+            let span = line_start..line_start + colon;
+            let corrected = procfile_diagnostics(text)
+                .iter()
+                .any(|diagnostic| diagnostic.fix.is_some() && diagnostic.span == span);
+            if corrected {
+                (format!("{KEY}\n\n{CORRECTED_KEY}"), span)
+            } else {
+                (KEY.to_string(), span)
+            }
+        } else {
+            (
+                VALUE.to_string(),
+                line_start + colon + 1..line_start + line.len(),
+            )
+        }
+    };
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: contents,
+        }),
+        range: Some(range(text, &span)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hover_text(text: &str, line: u32, character: u32) -> Option<String> {
+        hover(text, Position::new(line, character)).map(|hover| match hover.contents {
+            HoverContents::Markup(markup) => markup.value,
+            _ => unreachable!("hover contents are always markup"),
+        })
+    }
+
+    #[test]
+    fn hover_explains_the_hovered_part() {
+        let text = "# Processes\nweb: rails s\nWEB_2: puma\n\n";
+
+        assert_eq!(Some(COMMENT.to_string()), hover_text(text, 0, 3));
+        assert_eq!(Some(KEY.to_string()), hover_text(text, 1, 1));
+        assert_eq!(Some(VALUE.to_string()), hover_text(text, 1, 6));
+        assert_eq!(
+            Some(format!("{KEY}\n\n{CORRECTED_KEY}")),
+            hover_text(text, 2, 0)
+        );
+        assert_eq!(None, hover_text(text, 3, 0));
+    }
+}
@@ -0,0 +1,175 @@
+//! Language server for Procfiles, using the same parser as the buildpack.
+//!
+//! Speaks the Language Server Protocol over stdio and provides diagnostics, quick-fixes for
+//! corrected keys, hover text explaining the rules of `SPEC.md` and completion of common
+//! process types.
+
+mod completion;
+mod diagnostics;
+mod hover;
+mod position;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{CodeActionRequest, Completion, HoverRequest, Request as _};
+use lsp_types::{
+    CodeActionParams, CodeActionProviderCapability, CompletionOptions, CompletionParams,
+    CompletionResponse, HoverParams, HoverProviderCapability, PublishDiagnosticsParams,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, Uri,
+};
+use std::collections::HashMap;
+use std::error::Error;
+use std::process::ExitCode;
+
+// Dependencies only used by the `procfile` binary.
+use bullet_stream as _;
+use clap as _;
+use fs_err as _;
+use libcnb as _;
+use nix as _;
+use signal_hook as _;
+use toml as _;
+
+#[cfg(test)]
+use indoc as _;
+#[cfg(test)]
+use libcnb_test as _;
+
+/// Open documents by URI
+///
+/// Keyed by the URI string, as `Uri` has interior mutability.
+type Documents = HashMap<String, String>;
+
+fn main() -> ExitCode {
+    let (connection, io_threads) = Connection::stdio();
+
+    let result = serve(&connection);
+    drop(connection);
+    let result = result.and_then(|()| io_threads.join().map_err(Into::into));
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("procfile-language-server: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn serve(connection: &Connection) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..ServerCapabilities::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    let mut documents = Documents::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                connection
+                    .sender
+                    .send(respond(&documents, request).into())?;
+            }
+            Message::Notification(notification) => {
+                if let Some(uri) = update(&mut documents, notification)? {
+                    let text = documents.get(uri.as_str()).map(String::as_str);
+                    let params = PublishDiagnosticsParams {
+                        diagnostics: text.map(diagnostics::diagnostics).unwrap_or_default(),
+                        uri,
+                        version: None,
+                    };
+                    connection.sender.send(
+                        Notification::new(PublishDiagnostics::METHOD.to_string(), params).into(),
+                    )?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Applies document notifications, returning the document whose diagnostics need publishing
+fn update(
+    documents: &mut Documents,
+    notification: Notification,
+) -> Result<Option<Uri>, serde_json::Error> {
+    match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params: lsp_types::DidOpenTextDocumentParams =
+                serde_json::from_value(notification.params)?;
+            let uri = params.text_document.uri;
+            documents.insert(uri.to_string(), params.text_document.text);
+            Ok(Some(uri))
+        }
+        DidChangeTextDocument::METHOD => {
+            let params: lsp_types::DidChangeTextDocumentParams =
+                serde_json::from_value(notification.params)?;
+            let uri = params.text_document.uri;
+            // With full sync the last change contains the whole document
+            if let Some(change) = params.content_changes.into_iter().last() {
+                documents.insert(uri.to_string(), change.text);
+            }
+            Ok(Some(uri))
+        }
+        DidCloseTextDocument::METHOD => {
+            let params: lsp_types::DidCloseTextDocumentParams =
+                serde_json::from_value(notification.params)?;
+            documents.remove(params.text_document.uri.as_str());
+            Ok(Some(params.text_document.uri))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn respond(documents: &Documents, request: Request) -> Response {
+    let Request { id, method, params } = request;
+    let result = match method.as_str() {
+        HoverRequest::METHOD => serde_json::from_value(params).map(|params: HoverParams| {
+            let position = params.text_document_position_params;
+            let text = documents.get(position.text_document.uri.as_str());
+            serde_json::to_value(text.and_then(|text| hover::hover(text, position.position)))
+        }),
+        Completion::METHOD => serde_json::from_value(params).map(|params: CompletionParams| {
+            let position = params.text_document_position;
+            let items = documents
+                .get(position.text_document.uri.as_str())
+                .map(|text| completion::completions(text, position.position))
+                .unwrap_or_default();
+            serde_json::to_value(CompletionResponse::Array(items))
+        }),
+        CodeActionRequest::METHOD => {
+            serde_json::from_value(params).map(|params: CodeActionParams| {
+                let uri = params.text_document.uri;
+                let actions = documents
+                    .get(uri.as_str())
+                    .map(|text| diagnostics::code_actions(&uri, text, params.range))
+                    .unwrap_or_default();
+                serde_json::to_value(actions)
+            })
+        }
+        _ => {
+            return Response::new_err(
+                id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request `{method}`"),
+            );
+        }
+    };
+
+    match result.and_then(|value| value) {
+        Ok(value) => Response::new_ok(id, value),
+        Err(error) => Response::new_err(id, ErrorCode::InvalidParams as i32, error.to_string()),
+    }
+}
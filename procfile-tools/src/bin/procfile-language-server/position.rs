@@ -0,0 +1,73 @@
+//! Conversion between byte offsets and LSP positions, which count UTF-16 code units
+use lsp_types::{Position, Range};
+
+pub(crate) fn position(text: &str, offset: usize) -> Position {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    Position {
+        line: to_u32(before.matches('\n').count()),
+        character: to_u32(before[line_start..].encode_utf16().count()),
+    }
+}
+
+pub(crate) fn range(text: &str, span: &std::ops::Range<usize>) -> Range {
+    Range {
+        start: position(text, span.start),
+        end: position(text, span.end),
+    }
+}
+
+/// Byte offset of a position, clamped to the end of its line
+pub(crate) fn offset(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(index) => line_start += index + 1,
+            None => return text.len(),
+        }
+    }
+    let line = line(text, line_start);
+
+    let mut units = 0;
+    for (index, char) in line.char_indices() {
+        if units >= position.character as usize {
+            return line_start + index;
+        }
+        units += char.len_utf16();
+    }
+    line_start + line.len()
+}
+
+/// The line starting at `line_start`, without its line ending
+pub(crate) fn line(text: &str, line_start: usize) -> &str {
+    let rest = &text[line_start..];
+    rest[..rest.find('\n').unwrap_or(rest.len())].trim_end_matches('\r')
+}
+
+fn to_u32(value: usize) -> u32 {
+    u32::try_from(value).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_round_trip_through_positions() {
+        let text = "web: echo 😀 done\r\nworker: sidekiq";
+
+        for offset in [0, 10, 15, 24, text.len()] {
+            assert_eq!(offset, super::offset(text, position(text, offset)));
+        }
+        assert_eq!(Position::new(0, 12), position(text, 14));
+        assert_eq!(Position::new(1, 0), position(text, 21));
+    }
+
+    #[test]
+    fn offset_is_clamped_to_line_end() {
+        let text = "web: rails s\r\nworker: sidekiq";
+
+        assert_eq!(12, offset(text, Position::new(0, 100)));
+        assert_eq!(text.len(), offset(text, Position::new(5, 0)));
+    }
+}
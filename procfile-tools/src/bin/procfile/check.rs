@@ -0,0 +1,157 @@
+use crate::github::workflow_commands;
+use crate::sarif::{sarif_log, sarif_read_error_log};
+use bullet_stream::{Print, style};
+use clap::{Args, ValueEnum};
+use procfile_buildpack::{
+    BUILDPACK_RULES, Procfile, ProcfileBuildpackError, ProcfileDiagnostic, Severity,
+    apply_app_json, error_handler, print_error, procfile_diagnostics, procfile_launch,
+    procfile_messages,
+};
+use std::io::{stderr, stdout};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Validate a Procfile exactly like the buildpack does, without building an image
+///
+/// Lint rules the buildpack does not check are reported too. Like the buildpack, the check only
+/// fails for an invalid Procfile, unless `--strict` is used.
+#[derive(Args)]
+pub(crate) struct CheckArgs {
+    /// Path to the Procfile
+    #[arg(default_value = "Procfile")]
+    path: PathBuf,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    format: OutputFormat,
+    /// Fail when a lint rule reports an error, even though the buildpack accepts the Procfile
+    #[arg(long)]
+    strict: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    /// The same output as the buildpack, followed by lint findings
+    Human,
+    /// A SARIF 2.1 log for code scanning tools
+    Sarif,
+    /// GitHub Actions workflow commands that annotate the Procfile
+    Github,
+}
+
+pub(crate) fn run(args: &CheckArgs) -> ExitCode {
+    match args.format {
+        OutputFormat::Human => match check(&args.path) {
+            Ok(false) if args.strict => ExitCode::FAILURE,
+            Ok(_) => ExitCode::SUCCESS,
+            Err(error) => {
+                error_handler(error);
+                ExitCode::FAILURE
+            }
+        },
+        OutputFormat::Sarif => report(
+            &args.path,
+            args.strict,
+            |diagnostics| format!("{:#}\n", sarif_log(&args.path, diagnostics)),
+            // Consumers parse stdout, so the error is a SARIF notification there
+            |error| {
+                println!("{:#}", sarif_read_error_log(&args.path, &error));
+                print_error(
+                    stderr(),
+                    ProcfileBuildpackError::CannotReadProcfileContents(error),
+                );
+            },
+        ),
+        OutputFormat::Github => report(
+            &args.path,
+            args.strict,
+            |diagnostics| {
+                workflow_commands(&args.path, diagnostics)
+                    .into_iter()
+                    .map(|command| command + "\n")
+                    .collect()
+            },
+            |error| error_handler(ProcfileBuildpackError::CannotReadProcfileContents(error)),
+        ),
+    }
+}
+
+/// Prints the diagnostics in a machine-readable format, failing when the Procfile is invalid
+///
+/// With `strict`, errors of lint rules fail too. A Procfile that cannot be read is reported with
+/// `read_error`.
+fn report(
+    path: &Path,
+    strict: bool,
+    format: impl FnOnce(&[ProcfileDiagnostic]) -> String,
+    read_error: impl FnOnce(std::io::Error),
+) -> ExitCode {
+    let input = match fs_err::read_to_string(path) {
+        Ok(input) => input,
+        Err(error) => {
+            read_error(error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let diagnostics = procfile_diagnostics(&input);
+    print!("{}", format(&diagnostics));
+
+    if diagnostics.iter().any(|diagnostic| {
+        diagnostic.severity == Severity::Error
+            && (strict || BUILDPACK_RULES.contains(&diagnostic.rule))
+    }) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Returns false when a lint rule reports an error, see `--strict`
+fn check(path: &Path) -> Result<bool, ProcfileBuildpackError> {
+    let mut bullet = Print::new(stdout()).h2("Procfile Check").bullet(format!(
+        "Processes from {}",
+        style::value(path.to_string_lossy())
+    ));
+
+    let input =
+        fs_err::read_to_string(path).map_err(ProcfileBuildpackError::CannotReadProcfileContents)?;
+    let mut procfile: Procfile = input
+        .parse()
+        .map_err(ProcfileBuildpackError::ProcfileParsingError)?;
+    let app_dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let formation = apply_app_json(app_dir, &mut procfile);
+    for message in procfile_messages(&procfile) {
+        bullet = bullet.sub_bullet(message);
+    }
+    let mut output = bullet.done();
+
+    let lints = procfile_diagnostics(&input)
+        .into_iter()
+        .filter(|diagnostic| !BUILDPACK_RULES.contains(&diagnostic.rule))
+        .collect::<Vec<_>>();
+    if !lints.is_empty() {
+        let mut bullet = output.bullet("Lint");
+        for diagnostic in &lints {
+            let prefix = match diagnostic.severity {
+                Severity::Error => style::important("ERROR:"),
+                Severity::Warning => style::important("WARNING:"),
+            };
+            bullet = bullet.sub_bullet(format!(
+                "{prefix} {message} ({id} {name}, line {line})",
+                message = diagnostic.message,
+                id = diagnostic.rule.id,
+                name = diagnostic.rule.name,
+                line = diagnostic.start.line,
+            ));
+        }
+        output = bullet.done();
+    }
+    output.done();
+
+    procfile_launch(procfile, &formation)?;
+    Ok(lints
+        .iter()
+        .all(|diagnostic| diagnostic.severity != Severity::Error))
+}
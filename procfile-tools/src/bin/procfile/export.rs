@@ -0,0 +1,184 @@
+use bullet_stream::{Print, style};
+use clap::{Args, Subcommand};
+use procfile_buildpack::{
+    ComposeOptions, ExportError, ExportedFile, KubernetesOptions, Procfile, SystemdOptions,
+    compose_file, error_handler, kubernetes_manifests, read_procfile, systemd_units,
+};
+use std::io::stdout;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Export Procfile processes to other process managers
+#[derive(Args)]
+pub(crate) struct ExportArgs {
+    #[command(subcommand)]
+    format: ExportFormat,
+}
+
+#[derive(Subcommand)]
+enum ExportFormat {
+    /// A `compose.yaml` with a service per process
+    Compose(ComposeArgs),
+    /// One Deployment per process and a Service for the `web` process
+    Kubernetes(KubernetesArgs),
+    /// One systemd `.service` unit per process and a `.target` grouping them
+    Systemd(SystemdArgs),
+}
+
+#[derive(Args)]
+struct CommonArgs {
+    /// Path to the Procfile
+    #[arg(long, short = 'f', default_value = "Procfile")]
+    procfile: PathBuf,
+    /// Directory the files are written to
+    #[arg(long, short)]
+    output: PathBuf,
+    /// Name of the application, used to name the exported files
+    #[arg(long, short, default_value = "app")]
+    app: String,
+}
+
+#[derive(Args)]
+struct SystemdArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+    /// User the processes run as
+    #[arg(long, short)]
+    user: Option<String>,
+    /// Working directory of the processes, defaults to the directory of the Procfile
+    #[arg(long, short = 'd')]
+    working_directory: Option<PathBuf>,
+    /// File with environment variables to load
+    #[arg(long, short)]
+    env_file: Option<PathBuf>,
+    /// Restart policy of the services
+    #[arg(long, default_value = "on-failure", value_parser = ["no", "always", "on-success", "on-failure", "on-abnormal", "on-abort", "on-watchdog"])]
+    restart: String,
+    /// `PORT` of the first process, each following process gets a port 100 higher
+    #[arg(long, short, default_value_t = 5000)]
+    port: u16,
+}
+
+#[derive(Args)]
+struct ComposeArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+    /// Reference of the image built with the buildpack
+    #[arg(long, short)]
+    image: String,
+    /// `PORT` of the `web` process, published on the same host port
+    #[arg(long, short, default_value_t = 5000)]
+    port: u16,
+    /// File with environment variables to load
+    #[arg(long, short)]
+    env_file: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct KubernetesArgs {
+    #[command(flatten)]
+    common: CommonArgs,
+    /// Reference of the image built with the buildpack
+    #[arg(long, short)]
+    image: String,
+    /// `PORT` of the `web` process
+    #[arg(long, short, default_value_t = 5000)]
+    port: u16,
+}
+
+pub(crate) fn run(args: &ExportArgs) -> ExitCode {
+    match &args.format {
+        ExportFormat::Compose(args) => export(&args.common, |procfile| {
+            compose_file(
+                procfile,
+                &ComposeOptions {
+                    app: args.common.app.clone(),
+                    image: args.image.clone(),
+                    port: args.port,
+                    env_file: args.env_file.clone(),
+                },
+            )
+            .map(|file| vec![file])
+        }),
+        ExportFormat::Kubernetes(args) => export(&args.common, |procfile| {
+            kubernetes_manifests(
+                procfile,
+                &KubernetesOptions {
+                    app: args.common.app.clone(),
+                    image: args.image.clone(),
+                    port: args.port,
+                },
+            )
+        }),
+        ExportFormat::Systemd(args) => export(&args.common, |procfile| {
+            let working_directory = match &args.working_directory {
+                Some(directory) => directory.clone(),
+                None => app_dir(&args.common.procfile),
+            };
+            systemd_units(
+                procfile,
+                &SystemdOptions {
+                    app: args.common.app.clone(),
+                    user: args.user.clone(),
+                    working_directory,
+                    env_file: args.env_file.clone(),
+                    restart: args.restart.clone(),
+                    port: args.port,
+                },
+            )
+        }),
+    }
+}
+
+fn export(
+    args: &CommonArgs,
+    files: impl FnOnce(&Procfile) -> Result<Vec<ExportedFile>, ExportError>,
+) -> ExitCode {
+    let procfile = match read_procfile(&args.procfile) {
+        Ok(procfile) => procfile,
+        Err(error) => {
+            error_handler(error);
+            return ExitCode::FAILURE;
+        }
+    };
+    let files = match files(&procfile) {
+        Ok(files) => files,
+        Err(error) => {
+            Print::new(stdout())
+                .without_header()
+                .error(format!("Cannot export the processes\n\n{error}"));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut bullet = Print::new(stdout()).h2("Procfile Export").bullet(format!(
+        "Writing files to {}",
+        style::value(args.output.to_string_lossy())
+    ));
+    if let Err(error) = fs_err::create_dir_all(&args.output) {
+        bullet
+            .done()
+            .error(format!("Cannot create output directory\n\n{error}"));
+        return ExitCode::FAILURE;
+    }
+    for file in files {
+        if let Err(error) = fs_err::write(args.output.join(&file.name), file.contents) {
+            bullet
+                .done()
+                .error(format!("Cannot write {}\n\n{error}", file.name));
+            return ExitCode::FAILURE;
+        }
+        bullet = bullet.sub_bullet(style::value(file.name));
+    }
+    bullet.done().done();
+
+    ExitCode::SUCCESS
+}
+
+/// Absolute path of the directory containing the Procfile
+fn app_dir(procfile: &Path) -> PathBuf {
+    std::path::absolute(procfile)
+        .ok()
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
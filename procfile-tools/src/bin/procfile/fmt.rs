@@ -0,0 +1,63 @@
+use bullet_stream::{Print, style};
+use clap::Args;
+use procfile_buildpack::{ProcfileBuildpackError, ProcfileDocument, error_handler};
+use std::io::stdout;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Rewrite a Procfile into canonical form, keeping comments
+#[derive(Args)]
+pub(crate) struct FmtArgs {
+    /// Path to the Procfile
+    #[arg(default_value = "Procfile")]
+    path: PathBuf,
+    /// Exit non-zero instead of rewriting the Procfile when it is not formatted
+    #[arg(long)]
+    check: bool,
+}
+
+pub(crate) fn run(args: &FmtArgs) -> ExitCode {
+    let bullet = Print::new(stdout()).h2("Procfile Format").bullet(format!(
+        "Formatting {}",
+        style::value(args.path.to_string_lossy())
+    ));
+
+    let (input, mut document) = match read_document(&args.path) {
+        Ok(result) => result,
+        Err(error) => {
+            error_handler(error);
+            return ExitCode::FAILURE;
+        }
+    };
+    document.format();
+    let formatted = document.to_string();
+
+    if formatted == input {
+        bullet.sub_bullet("Already formatted").done().done();
+        ExitCode::SUCCESS
+    } else if args.check {
+        bullet.done().error(format!(
+            "Procfile is not formatted\n\nRun {} to format it.",
+            style::command(format!("procfile fmt {}", args.path.to_string_lossy()))
+        ));
+        ExitCode::FAILURE
+    } else if let Err(error) = fs_err::write(&args.path, formatted) {
+        bullet
+            .done()
+            .error(format!("Cannot write formatted Procfile\n\n{error}"));
+        ExitCode::FAILURE
+    } else {
+        bullet.sub_bullet("Formatted").done().done();
+        ExitCode::SUCCESS
+    }
+}
+
+fn read_document(path: &Path) -> Result<(String, ProcfileDocument), ProcfileBuildpackError> {
+    let input =
+        fs_err::read_to_string(path).map_err(ProcfileBuildpackError::CannotReadProcfileContents)?;
+    let document = input
+        .parse()
+        .map_err(ProcfileBuildpackError::ProcfileParsingError)?;
+
+    Ok((input, document))
+}
@@ -0,0 +1,72 @@
+//! Diagnostics as GitHub Actions workflow commands, which annotate the lines of a diff
+use procfile_buildpack::{ProcfileDiagnostic, Severity};
+use std::path::Path;
+
+/// One `::error …::message` or `::warning …::message` line per diagnostic
+pub(crate) fn workflow_commands(path: &Path, diagnostics: &[ProcfileDiagnostic]) -> Vec<String> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let command = match diagnostic.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            let properties = [
+                ("file", path.to_string_lossy().to_string()),
+                ("line", diagnostic.start.line.to_string()),
+                ("col", diagnostic.start.column.to_string()),
+                ("endLine", diagnostic.end.line.to_string()),
+                ("endColumn", diagnostic.end.column.to_string()),
+                (
+                    "title",
+                    format!("{} {}", diagnostic.rule.id, diagnostic.rule.name),
+                ),
+            ]
+            .iter()
+            .map(|(name, value)| format!("{name}={}", escape_property(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+
+            format!(
+                "::{command} {properties}::{}",
+                escape_data(&diagnostic.message)
+            )
+        })
+        .collect()
+}
+
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_messages_and_properties() {
+        assert_eq!("100%25%0Adone: ok", escape_data("100%\ndone: ok"));
+        assert_eq!("C%3A\\app%2Cv2", escape_property("C:\\app,v2"));
+    }
+
+    #[test]
+    fn escapes_file_and_title_properties() {
+        let diagnostics = procfile_buildpack::procfile_diagnostics("web:\n");
+        let commands = workflow_commands(Path::new("apps/a,b:c/100%/Procfile"), &diagnostics);
+
+        assert_eq!(1, commands.len());
+        assert!(
+            commands[0].starts_with("::error file=apps/a%2Cb%3Ac/100%25/Procfile,line=1,"),
+            "{}",
+            commands[0]
+        );
+        assert!(commands[0].contains(",title=P001 invalid-procfile::"));
+    }
+}
@@ -0,0 +1,74 @@
+use bullet_stream::{Print, style};
+use clap::Args;
+use libcnb::data::launch::Launch;
+use procfile_buildpack::{
+    ProcfileBuildpackError, apply_app_json, error_handler, procfile_launch, procfile_messages,
+    read_procfile,
+};
+use std::io::{stderr, stdout};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Print the `launch.toml` the buildpack would write for a Procfile
+///
+/// The `formation` of an `app.json` next to the Procfile is applied like the buildpack does. The TOML is written to stdout, warnings and the default process to stderr.
+#[derive(Args)]
+pub(crate) struct LaunchArgs {
+    /// Path to the Procfile
+    #[arg(default_value = "Procfile")]
+    path: PathBuf,
+}
+
+pub(crate) fn run(args: &LaunchArgs) -> ExitCode {
+    let launch = match read_launch(&args.path) {
+        Ok(launch) => launch,
+        Err(error) => {
+            error_handler(error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match toml::to_string(&launch) {
+        Ok(launch_toml) => {
+            print!("{launch_toml}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            Print::new(stdout())
+                .without_header()
+                .error(format!("Cannot serialize launch.toml\n\n{error}"));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn read_launch(path: &Path) -> Result<Launch, ProcfileBuildpackError> {
+    let mut bullet = Print::new(stderr()).h2("Procfile Launch").bullet(format!(
+        "Processes from {}",
+        style::value(path.to_string_lossy())
+    ));
+
+    let mut procfile = read_procfile(path)?;
+    let app_dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let formation = apply_app_json(app_dir, &mut procfile);
+    for message in procfile_messages(&procfile) {
+        bullet = bullet.sub_bullet(message);
+    }
+
+    let default = match procfile.default_process_type_with(&formation) {
+        Some(process_type) => format!("Default process: {}", style::value(process_type)),
+        None if procfile.processes().is_empty() => {
+            String::from("No default process, no processes are defined")
+        }
+        None => format!(
+            "No default process, more than one process is defined and none is {} or in the app.json formation",
+            style::value("web")
+        ),
+    };
+    bullet.done().bullet(default).done().done();
+
+    procfile_launch(procfile, &formation)
+}
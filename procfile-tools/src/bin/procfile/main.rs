@@ -0,0 +1,47 @@
+//! Command-line tools for working with Procfiles, using the same logic as the buildpack.
+
+mod check;
+mod export;
+mod fmt;
+mod github;
+mod launch;
+mod run;
+mod sarif;
+
+use clap::{Parser, Subcommand};
+use std::process::ExitCode;
+
+// Dependencies only used by the language server binary.
+use lsp_server as _;
+use lsp_types as _;
+
+#[cfg(test)]
+use indoc as _;
+#[cfg(test)]
+use libcnb_test as _;
+
+#[derive(Parser)]
+#[command(about = "Tools for working with Procfiles")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    Check(check::CheckArgs),
+    Export(export::ExportArgs),
+    Fmt(fmt::FmtArgs),
+    Launch(launch::LaunchArgs),
+    Run(run::RunArgs),
+}
+
+fn main() -> ExitCode {
+    match Cli::parse().command {
+        Command::Check(args) => check::run(&args),
+        Command::Export(args) => export::run(&args),
+        Command::Fmt(args) => fmt::run(&args),
+        Command::Launch(args) => launch::run(&args),
+        Command::Run(args) => run::run(&args),
+    }
+}
@@ -0,0 +1,273 @@
+use bullet_stream::{Print, strip_ansi, style};
+use clap::Args;
+use nix::sys::signal::{Signal, killpg};
+use nix::unistd::Pid;
+use procfile_buildpack::{Procfile, error_handler, process_ports, read_procfile};
+use signal_hook::consts::{SIGINT, SIGTERM};
+use std::env;
+use std::io::{BufRead, BufReader, IsTerminal, Read, Write, stdout};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitCode, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Run Procfile processes locally, with `bash -c` like the buildpack does
+#[derive(Args)]
+pub(crate) struct RunArgs {
+    /// Process types to run, all processes are run when omitted
+    process_types: Vec<String>,
+    /// Path to the Procfile, processes run in its directory
+    #[arg(long, short = 'f', default_value = "Procfile")]
+    procfile: PathBuf,
+    /// `PORT` of the first process, each following process gets a port 100 higher
+    #[arg(long, short, default_value_t = 5000)]
+    port: u16,
+    /// Seconds to wait for processes to stop before killing them
+    #[arg(long, default_value_t = 5)]
+    timeout: u64,
+}
+
+struct Process {
+    name: String,
+    child: Child,
+}
+
+pub(crate) fn run(args: &RunArgs) -> ExitCode {
+    let procfile = match read_procfile(&args.procfile) {
+        Ok(procfile) => procfile,
+        Err(error) => {
+            error_handler(error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Some(selected) = select(&procfile, &args.process_types) else {
+        return ExitCode::FAILURE;
+    };
+
+    let shutdown = Arc::new(AtomicBool::new(false));
+    for signal in [SIGINT, SIGTERM] {
+        if let Err(error) = signal_hook::flag::register(signal, Arc::clone(&shutdown)) {
+            Print::new(stdout())
+                .without_header()
+                .error(format!("Cannot handle shutdown signals\n\n{error}"));
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let Some(ports) = process_ports(args.port, selected.len()) else {
+        Print::new(stdout()).without_header().error(format!(
+            "Cannot assign ports to {count} processes starting at {port}\n\n\
+            Each process gets a port 100 higher than the previous one and the highest port is 65535. \
+            Use a lower {flag} or run fewer processes.",
+            count = selected.len(),
+            port = args.port,
+            flag = style::value("--port")
+        ));
+        return ExitCode::FAILURE;
+    };
+
+    let app_dir = args
+        .procfile
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let width = selected
+        .iter()
+        .map(|(name, _)| name.len())
+        .chain(["system".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut bullet = Print::new(stdout()).h2("Procfile Run").bullet(format!(
+        "Processes from {}",
+        style::value(args.procfile.to_string_lossy())
+    ));
+    let mut processes = Vec::new();
+    let mut outputs = Vec::new();
+    let colored = stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+    for ((name, command), port) in selected.into_iter().zip(ports) {
+        bullet = bullet.sub_bullet(format!(
+            "{name}: {cmd} (PORT={port})",
+            cmd = style::command(command)
+        ));
+
+        match start(command, port, app_dir) {
+            Ok(mut child) => {
+                let prefix = style::important(format!("{name:width$} |"));
+                let prefix = if colored { prefix } else { strip_ansi(prefix) };
+                outputs.push((child.stdout.take(), child.stderr.take(), prefix));
+                processes.push(Process {
+                    name: name.clone(),
+                    child,
+                });
+            }
+            Err(error) => {
+                bullet
+                    .done()
+                    .error(format!("Cannot start process {name}\n\n{error}"));
+                stop(&mut processes, Duration::from_secs(args.timeout));
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    bullet.done().done();
+
+    // Output is forwarded once the header is complete, it waits in the pipes until then
+    let mut forwarders = Vec::new();
+    for (stdout, stderr, prefix) in outputs {
+        if let Some(output) = stdout {
+            forwarders.push(forward(output, prefix.clone()));
+        }
+        if let Some(output) = stderr {
+            forwarders.push(forward(output, prefix));
+        }
+    }
+
+    let exit_code = supervise(&mut processes, &shutdown, &format!("{:width$} |", "system"));
+    stop(&mut processes, Duration::from_secs(args.timeout));
+    for forwarder in forwarders {
+        let _ = forwarder.join();
+    }
+
+    exit_code
+}
+
+/// Returns the requested processes, or all processes when none are requested
+///
+/// Prints an error and returns `None` when a requested process type is not defined.
+fn select<'a>(
+    procfile: &'a Procfile,
+    process_types: &[String],
+) -> Option<Vec<(&'a String, &'a String)>> {
+    if process_types.is_empty() {
+        return Some(procfile.processes().iter().collect());
+    }
+
+    let selected = process_types
+        .iter()
+        .map(|process_type| {
+            procfile
+                .processes()
+                .iter()
+                .find(|(key, _)| *key == process_type)
+                .ok_or(process_type)
+        })
+        .collect::<Result<Vec<_>, _>>();
+    match selected {
+        Ok(selected) => Some(selected),
+        Err(process_type) => {
+            let known = procfile
+                .processes()
+                .keys()
+                .map(style::value)
+                .collect::<Vec<_>>()
+                .join(", ");
+            Print::new(stdout()).without_header().error(format!(
+                "Unknown process type {}\n\nThe Procfile defines: {known}",
+                style::value(process_type)
+            ));
+            None
+        }
+    }
+}
+
+fn start(command: &str, port: u16, app_dir: &Path) -> std::io::Result<Child> {
+    // Each process gets its own process group, so Ctrl-C is only delivered to this runner
+    // and shutdown signals reach everything started by `bash -c`.
+    Command::new("bash")
+        .args(["-c", command])
+        .env("PORT", port.to_string())
+        .current_dir(app_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()
+}
+
+/// Waits until a shutdown signal is received or any process exits
+fn supervise(processes: &mut [Process], shutdown: &AtomicBool, system: &str) -> ExitCode {
+    loop {
+        if shutdown.load(Ordering::Relaxed) {
+            log(system, "Shutdown signal received, stopping all processes");
+            return ExitCode::SUCCESS;
+        }
+        if let Some((name, status)) = processes.iter_mut().find_map(|process| {
+            process
+                .child
+                .try_wait()
+                .ok()
+                .flatten()
+                .map(|status| (process.name.clone(), status))
+        }) {
+            log(
+                system,
+                &format!("{name} exited with {status}, stopping all processes"),
+            );
+            return exit_code(status);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Prints every line of the stream with the process prefix
+fn forward(stream: impl Read + Send + 'static, prefix: String) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut line = Vec::new();
+        while reader
+            .read_until(b'\n', &mut line)
+            .is_ok_and(|read| read > 0)
+        {
+            log(
+                &prefix,
+                String::from_utf8_lossy(&line).trim_end_matches(['\n', '\r']),
+            );
+            line.clear();
+        }
+    })
+}
+
+fn log(prefix: &str, message: &str) {
+    let _ = writeln!(stdout().lock(), "{prefix} {message}");
+}
+
+/// Sends `SIGTERM` to all running processes and `SIGKILL` to those still running after the timeout
+fn stop(processes: &mut [Process], timeout: Duration) {
+    signal(processes, Signal::SIGTERM);
+
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline
+        && processes
+            .iter_mut()
+            .any(|process| matches!(process.child.try_wait(), Ok(None)))
+    {
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    signal(processes, Signal::SIGKILL);
+    for process in processes {
+        let _ = process.child.wait();
+    }
+}
+
+fn signal(processes: &[Process], signal: Signal) {
+    // The group is signalled even when its leader has exited, so processes it left behind
+    // (like the `puma` of `bash -c "puma & sleep 1"`) are stopped too.
+    for process in processes {
+        if let Ok(pid) = i32::try_from(process.child.id()) {
+            let _ = killpg(Pid::from_raw(pid), signal);
+        }
+    }
+}
+
+fn exit_code(status: ExitStatus) -> ExitCode {
+    status
+        .code()
+        .and_then(|code| u8::try_from(code).ok())
+        .map_or(ExitCode::FAILURE, ExitCode::from)
+}
@@ -0,0 +1,153 @@
+//! Diagnostics as a SARIF 2.1 log, for code scanning tools
+use procfile_buildpack::{ProcfileDiagnostic, RULES, Severity};
+use serde_json::{Value, json};
+use std::path::{Component, Path, PathBuf};
+
+pub(crate) fn sarif_log(path: &Path, diagnostics: &[ProcfileDiagnostic]) -> Value {
+    let uri = artifact_uri(path);
+    let rules = RULES
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id,
+                "name": rule.name,
+                "shortDescription": { "text": rule.description },
+                "defaultConfiguration": { "level": level(rule.severity) },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let results = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            json!({
+                "ruleId": diagnostic.rule.id,
+                "ruleIndex": RULES.iter().position(|rule| *rule == diagnostic.rule),
+                "level": level(diagnostic.severity),
+                "message": { "text": diagnostic.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                        "region": {
+                            "startLine": diagnostic.start.line,
+                            "startColumn": diagnostic.start.column,
+                            "endLine": diagnostic.end.line,
+                            "endColumn": diagnostic.end.column,
+                        },
+                    },
+                }],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "procfile",
+                    "informationUri": "https://github.com/heroku/buildpacks-procfile",
+                    "rules": rules,
+                },
+            },
+            // Columns are counted in characters, like `SourcePosition`
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+/// A SARIF log without results for a Procfile that cannot be read, with the error as a tool
+/// execution notification
+pub(crate) fn sarif_read_error_log(path: &Path, error: &std::io::Error) -> Value {
+    let mut log = sarif_log(path, &[]);
+    log["runs"][0]["invocations"] = json!([{
+        "executionSuccessful": false,
+        "toolExecutionNotifications": [{
+            "level": "error",
+            "message": { "text": format!("Cannot read Procfile contents: {error}") },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": artifact_uri(path) },
+                },
+            }],
+        }],
+    }]);
+    log
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+/// The Procfile path as a URI relative to the working directory, where code scanning runs
+fn artifact_uri(path: &Path) -> String {
+    match (std::env::current_dir(), std::path::absolute(path)) {
+        (Ok(base), Ok(path)) => relative_uri(&base, &path),
+        _ => relative_uri(Path::new(""), path),
+    }
+}
+
+/// A relative, percent-encoded URI reference to `path` from the `base` directory
+fn relative_uri(base: &Path, path: &Path) -> String {
+    let common = base
+        .components()
+        .zip(path.components())
+        .take_while(|(base, path)| base == path)
+        .count();
+    let relative = base
+        .components()
+        .skip(common)
+        .map(|_| Component::ParentDir)
+        .chain(path.components().skip(common))
+        .collect::<PathBuf>();
+
+    relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(segment) => Some(percent_encode(&segment.to_string_lossy())),
+            Component::ParentDir => Some(String::from("..")),
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Encodes every byte except the unreserved characters of RFC 3986
+fn percent_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_percent_encoded_uris() {
+        assert_eq!(
+            "tests/my%20app/Procfile",
+            relative_uri(Path::new("/repo"), Path::new("/repo/tests/my app/Procfile"))
+        );
+        assert_eq!(
+            "../other/100%25/Procfile",
+            relative_uri(Path::new("/repo"), Path::new("/other/100%/Procfile"))
+        );
+        assert_eq!(
+            "caf%C3%A9/Procfile",
+            relative_uri(Path::new(""), Path::new("./café/Procfile"))
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! Tests for the `procfile-language-server` binary. These speak the protocol over stdio and are fast.
+
+// Required due to: https://github.com/rust-lang/rust/issues/95513
+#![allow(unused_crate_dependencies)]
+
+use lsp_server::{Message, Notification, Request, RequestId};
+use serde_json::{Value, json};
+use std::io::BufReader;
+use std::process::{Command, Stdio};
+
+#[test]
+fn test_diagnostics_and_quick_fix() {
+    let mut server = Command::new(env!("CARGO_BIN_EXE_procfile-language-server"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = server.stdin.take().unwrap();
+    let mut stdout = BufReader::new(server.stdout.take().unwrap());
+    let mut send = |message: Message| message.write(&mut stdin).unwrap();
+    let uri = "file:///app/Procfile";
+
+    send(
+        Request::new(
+            RequestId::from(1),
+            "initialize".to_string(),
+            json!({"capabilities": {}}),
+        )
+        .into(),
+    );
+    let Some(Message::Response(response)) = Message::read(&mut stdout).unwrap() else {
+        panic!("expected initialize response")
+    };
+    // Full document sync
+    assert_eq!(
+        json!(1),
+        response.result.unwrap_or(Value::Null)["capabilities"]["textDocumentSync"]
+    );
+    send(Notification::new("initialized".to_string(), json!({})).into());
+
+    send(
+        Notification::new(
+            "textDocument/didOpen".to_string(),
+            json!({"textDocument": {
+                "uri": uri,
+                "languageId": "procfile",
+                "version": 1,
+                "text": "web: rails s -p $PORT\nWorker_1: sidekiq\n",
+            }}),
+        )
+        .into(),
+    );
+    let Some(Message::Notification(notification)) = Message::read(&mut stdout).unwrap() else {
+        panic!("expected diagnostics notification")
+    };
+    assert_eq!("textDocument/publishDiagnostics", notification.method);
+    assert_eq!(
+        json!([{
+            "range": {"start": {"line": 1, "character": 0}, "end": {"line": 1, "character": 8}},
+            "severity": 2,
+            "code": "P002",
+            "source": "procfile",
+            "message": "Procfile key `Worker_1` has been corrected to `worker-1`. Please update your Procfile.",
+        }]),
+        notification.params["diagnostics"]
+    );
+
+    send(
+        Request::new(
+            RequestId::from(2),
+            "textDocument/codeAction".to_string(),
+            json!({
+                "textDocument": {"uri": uri},
+                "range": {"start": {"line": 1, "character": 3}, "end": {"line": 1, "character": 3}},
+                "context": {"diagnostics": []},
+            }),
+        )
+        .into(),
+    );
+    let Some(Message::Response(response)) = Message::read(&mut stdout).unwrap() else {
+        panic!("expected code action response")
+    };
+    let result = response.result.unwrap_or(Value::Null);
+    assert_eq!(json!("Rename `Worker_1` to `worker-1`"), result[0]["title"]);
+    assert_eq!(
+        json!("worker-1"),
+        result[0]["edit"]["changes"][uri][0]["newText"]
+    );
+
+    send(Request::new(RequestId::from(3), "shutdown".to_string(), json!(null)).into());
+    let Some(Message::Response(_)) = Message::read(&mut stdout).unwrap() else {
+        panic!("expected shutdown response")
+    };
+    send(Notification::new("exit".to_string(), json!(null)).into());
+    assert!(server.wait().unwrap().success());
+}
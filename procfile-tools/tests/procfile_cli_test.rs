@@ -0,0 +1,454 @@
+//! Tests for the `procfile` command-line tool. These run the binary directly and are fast.
+
+// Required due to: https://github.com/rust-lang/rust/issues/95513
+#![allow(unused_crate_dependencies)]
+
+use bullet_stream::strip_ansi;
+use indoc::indoc;
+use libcnb_test::assert_contains;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn procfile(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_procfile"))
+        .args(args)
+        .output()
+        .expect("procfile binary should run")
+}
+
+fn stdout(output: &Output) -> String {
+    strip_ansi(String::from_utf8_lossy(&output.stdout))
+}
+
+#[test]
+fn test_check_valid_procfile() {
+    let output = procfile(&["check", "tests/fixtures/not_yaml_procfile/Procfile"]);
+
+    assert!(output.status.success());
+    assert_contains!(
+        stdout(&output),
+        "- WARNING: Procfile key `   web` has been corrected to `web`. Please update your Procfile."
+    );
+    assert_contains!(stdout(&output), "- web: `echo foo: bar   `");
+}
+
+#[test]
+fn test_check_invalid_procfile() {
+    let output = procfile(&["check", "tests/fixtures/invalid_procfile/Procfile"]);
+
+    assert!(!output.status.success());
+    assert_contains!(stdout(&output), "Invalid Procfile format");
+    assert_contains!(stdout(&output), "2 | is_w.e.b: echo hello");
+}
+
+#[test]
+fn test_check_missing_procfile() {
+    let output = procfile(&["check", "tests/fixtures/missing_procfile/Procfile"]);
+
+    assert!(!output.status.success());
+    assert_contains!(stdout(&output), "Cannot read Procfile contents");
+}
+
+#[test]
+fn test_fmt_check_formatted_procfile() {
+    let output = procfile(&[
+        "fmt",
+        "--check",
+        "tests/fixtures/web_and_worker_procfile/Procfile",
+    ]);
+
+    assert!(output.status.success());
+    assert_contains!(stdout(&output), "Already formatted");
+}
+
+#[test]
+fn test_fmt_check_unformatted_procfile() {
+    let output = procfile(&[
+        "fmt",
+        "--check",
+        "tests/fixtures/unformatted_procfile/Procfile",
+    ]);
+
+    assert!(!output.status.success());
+    assert_contains!(stdout(&output), "Procfile is not formatted");
+}
+
+#[test]
+fn test_fmt_rewrites_procfile() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("fmt_Procfile");
+    fs_err::copy("tests/fixtures/unformatted_procfile/Procfile", &path).unwrap();
+
+    let output = procfile(&["fmt", &path.to_string_lossy()]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        "# Processes\nweb-server: bundle exec puma\n\nworker: sidekiq\n",
+        fs_err::read_to_string(&path).unwrap()
+    );
+}
+
+#[test]
+fn test_run_single_process() {
+    let output = procfile(&[
+        "run",
+        "--procfile",
+        "tests/fixtures/complex_command_procfile/Procfile",
+        "--port",
+        "12345",
+        "web",
+    ]);
+
+    assert!(output.status.success());
+    assert_contains!(
+        stdout(&output),
+        "web    | this is the \"web\" process!\nweb    | \"PORT\" is set to: '12345'\n"
+    );
+}
+
+#[test]
+fn test_run_unknown_process_type() {
+    let output = procfile(&[
+        "run",
+        "--procfile",
+        "tests/fixtures/web_and_worker_procfile/Procfile",
+        "console",
+    ]);
+
+    assert!(!output.status.success());
+    assert_contains!(stdout(&output), "Unknown process type `console`");
+}
+
+#[test]
+fn test_run_port_overflow() {
+    let output = procfile(&[
+        "run",
+        "--procfile",
+        "tests/fixtures/web_and_worker_procfile/Procfile",
+        "--port",
+        "65500",
+    ]);
+
+    assert!(!output.status.success());
+    assert_contains!(
+        stdout(&output),
+        "Cannot assign ports to 2 processes starting at 65500"
+    );
+}
+
+#[test]
+fn test_check_lint_warnings() {
+    let output = procfile(&["check", "tests/fixtures/web_and_worker_procfile/Procfile"]);
+
+    assert!(output.status.success());
+    assert_contains!(
+        stdout(&output),
+        "- Lint\n  - WARNING: The `web` process never references `$PORT`."
+    );
+    assert_contains!(stdout(&output), "(P010 web-port, line 1)");
+}
+
+#[test]
+fn test_check_lint_errors_fail() {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join("Procfile.secret");
+    std::fs::write(
+        &path,
+        "web: DATABASE_URL=postgres://app:s3cr3t@db/app rails s -p $PORT\n",
+    )
+    .unwrap();
+    let output = procfile(&["check", "--strict", &path.to_string_lossy()]);
+
+    assert!(!output.status.success());
+    assert_contains!(
+        stdout(&output),
+        "- ERROR: A password is hardcoded in a URL."
+    );
+    // The buildpack accepts the Procfile, so only `--strict` fails
+    assert!(
+        procfile(&["check", &path.to_string_lossy()])
+            .status
+            .success()
+    );
+    assert!(
+        stdout(&output)
+            .lines()
+            .filter(|line| line.contains("ERROR:"))
+            .all(|line| !line.contains("s3cr3t"))
+    );
+}
+
+#[test]
+fn test_launch_toml() {
+    let output = procfile(&["launch", "tests/fixtures/web_and_worker_procfile/Procfile"]);
+
+    assert!(output.status.success());
+    assert_contains!(
+        stdout(&output),
+        indoc! {r#"
+            [[processes]]
+            type = "web"
+            command = ["bash", "-c"]
+            args = ["echo 'this is the web process!'"]
+            default = true
+
+            [[processes]]
+            type = "worker"
+            command = ["bash", "-c"]
+            args = ["echo 'this is the worker process!'"]
+        "#}
+    );
+    assert_contains!(
+        strip_ansi(String::from_utf8_lossy(&output.stderr)),
+        "- Default process: `web`"
+    );
+}
+
+#[test]
+fn test_launch_app_json_formation() {
+    let output = procfile(&["launch", "tests/fixtures/formation_procfile/Procfile"]);
+
+    assert!(output.status.success());
+    assert_contains!(
+        stdout(&output),
+        indoc! {r#"
+            type = "worker"
+            command = ["bash", "-c"]
+            args = ["echo 'this is the worker process!'"]
+            default = true
+        "#}
+    );
+    let stderr = strip_ansi(String::from_utf8_lossy(&output.stderr));
+    assert_contains!(
+        stderr,
+        "- WARNING: Process type `release` in the app.json formation is not defined and will not run."
+    );
+    assert_contains!(stderr, "- Default process: `worker`");
+}
+
+#[test]
+fn test_launch_invalid_app_json() {
+    let app_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("invalid_app_json");
+    std::fs::create_dir_all(&app_dir).unwrap();
+    std::fs::write(app_dir.join("Procfile"), "web: rails s -p $PORT\n").unwrap();
+    std::fs::write(app_dir.join("app.json"), r#"{"formation": ["web"]}"#).unwrap();
+    let output = procfile(&["launch", &app_dir.join("Procfile").to_string_lossy()]);
+
+    assert!(output.status.success());
+    let stderr = strip_ansi(String::from_utf8_lossy(&output.stderr));
+    assert_contains!(
+        stderr,
+        "- WARNING: The app.json is ignored, its formation cannot be used to choose the default process."
+    );
+    assert_contains!(stderr, "- Default process: `web`");
+}
+
+#[test]
+fn test_check_app_json_formation() {
+    let output = procfile(&["check", "tests/fixtures/formation_procfile/Procfile"]);
+
+    assert!(output.status.success());
+    assert_contains!(
+        stdout(&output),
+        "- WARNING: Process type `release` in the app.json formation is not defined and will not run."
+    );
+}
+
+#[test]
+fn test_export_systemd() {
+    let output_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("export_systemd");
+    let output = procfile(&[
+        "export",
+        "systemd",
+        "--procfile",
+        "tests/fixtures/web_and_worker_procfile/Procfile",
+        "--output",
+        &output_dir.to_string_lossy(),
+        "--app",
+        "example",
+        "--working-directory",
+        "/srv/example",
+    ]);
+
+    assert!(output.status.success());
+    assert_contains!(
+        fs_err::read_to_string(output_dir.join("example-worker.service")).unwrap(),
+        "ExecStart=/bin/bash -c \"echo 'this is the worker process!'\""
+    );
+    assert_contains!(
+        fs_err::read_to_string(output_dir.join("example.target")).unwrap(),
+        "Wants=example-web.service example-worker.service"
+    );
+}
+
+#[test]
+fn test_export_systemd_port_overflow() {
+    let output_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("export_systemd_port_overflow");
+    let output = procfile(&[
+        "export",
+        "systemd",
+        "--procfile",
+        "tests/fixtures/web_and_worker_procfile/Procfile",
+        "--output",
+        &output_dir.to_string_lossy(),
+        "--port",
+        "65500",
+    ]);
+
+    assert!(!output.status.success());
+    assert_contains!(
+        stdout(&output),
+        "Cannot assign ports to 2 processes starting at 65500."
+    );
+    assert!(!output_dir.exists());
+}
+
+#[test]
+fn test_export_kubernetes() {
+    let output_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("export_kubernetes");
+    let output = procfile(&[
+        "export",
+        "kubernetes",
+        "--procfile",
+        "tests/fixtures/web_and_worker_procfile/Procfile",
+        "--output",
+        &output_dir.to_string_lossy(),
+        "--app",
+        "example",
+        "--image",
+        "example:latest",
+    ]);
+
+    assert!(output.status.success());
+    assert_contains!(
+        fs_err::read_to_string(output_dir.join("example-web.yaml")).unwrap(),
+        "kind: Service"
+    );
+    assert_contains!(
+        fs_err::read_to_string(output_dir.join("example-worker.yaml")).unwrap(),
+        "- /cnb/process/worker"
+    );
+}
+
+#[test]
+fn test_export_kubernetes_invalid_app() {
+    let output_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("export_kubernetes_invalid_app");
+    let output = procfile(&[
+        "export",
+        "kubernetes",
+        "--procfile",
+        "tests/fixtures/web_and_worker_procfile/Procfile",
+        "--output",
+        &output_dir.to_string_lossy(),
+        "--app",
+        "My_App",
+        "--image",
+        "example:latest",
+    ]);
+
+    assert!(!output.status.success());
+    assert_contains!(stdout(&output), "The app name `My_App` is not valid");
+    assert!(!output_dir.exists());
+}
+
+#[test]
+fn test_export_compose() {
+    let output_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("export_compose");
+    let output = procfile(&[
+        "export",
+        "compose",
+        "--procfile",
+        "tests/fixtures/web_and_worker_procfile/Procfile",
+        "--output",
+        &output_dir.to_string_lossy(),
+        "--image",
+        "example:latest",
+        "--env-file",
+        ".env",
+    ]);
+
+    assert!(output.status.success());
+    assert_contains!(
+        fs_err::read_to_string(output_dir.join("compose.yaml")).unwrap(),
+        "entrypoint:\n      - /cnb/process/worker\n    env_file:\n      - \".env\"\n"
+    );
+}
+
+#[test]
+fn test_check_sarif() {
+    let output = procfile(&[
+        "check",
+        "--format",
+        "sarif",
+        "tests/fixtures/invalid_procfile/Procfile",
+    ]);
+
+    assert!(!output.status.success());
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!("2.1.0", log["version"]);
+    let result = &log["runs"][0]["results"][0];
+    assert_eq!("P001", result["ruleId"]);
+    assert_eq!("error", result["level"]);
+    assert_eq!(
+        "tests/fixtures/invalid_procfile/Procfile",
+        result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"]
+    );
+}
+
+#[test]
+fn test_check_sarif_missing_procfile() {
+    let output = procfile(&[
+        "check",
+        "--format",
+        "sarif",
+        "tests/fixtures/missing_procfile/Procfile",
+    ]);
+
+    assert!(!output.status.success());
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let invocation = &log["runs"][0]["invocations"][0];
+    assert_eq!(false, invocation["executionSuccessful"]);
+    assert_eq!(
+        "error",
+        invocation["toolExecutionNotifications"][0]["level"]
+    );
+    assert_contains!(
+        invocation["toolExecutionNotifications"][0]["message"]["text"]
+            .as_str()
+            .unwrap(),
+        "Cannot read Procfile contents: "
+    );
+    assert_eq!(Some(&Vec::new()), log["runs"][0]["results"].as_array());
+    assert_contains!(
+        strip_ansi(String::from_utf8_lossy(&output.stderr)),
+        "Cannot read Procfile contents"
+    );
+}
+
+#[test]
+fn test_check_github_annotations() {
+    let output = procfile(&[
+        "check",
+        "--format",
+        "github",
+        "tests/fixtures/invalid_procfile/Procfile",
+    ]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        "::error file=tests/fixtures/invalid_procfile/Procfile,line=2,col=5,endLine=2,endColumn=6,title=P001 invalid-procfile::invalid inner key character%0Aexpected lowercase alphanum (a-z0-9) or `-`, keys contain characters or fewer\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+#[test]
+fn test_check_github_missing_procfile() {
+    let output = procfile(&[
+        "check",
+        "--format",
+        "github",
+        "tests/fixtures/missing_procfile/Procfile",
+    ]);
+
+    assert!(!output.status.success());
+    assert_contains!(stdout(&output), "Cannot read Procfile contents");
+    assert!(!stdout(&output).contains("::error"));
+}
@@ -0,0 +1,136 @@
+//! Contains logic for reading the `formation` of an `app.json`
+use crate::{Procfile, ProcfileWarning};
+use serde_json::Value;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum AppJsonError {
+    /// The file is not valid JSON
+    InvalidJson(String),
+    /// The `formation` is not a mapping of process types to objects with a `quantity`
+    InvalidFormation,
+}
+
+impl Display for AppJsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppJsonError::InvalidJson(error) => write!(f, "Invalid JSON: {error}"),
+            AppJsonError::InvalidFormation => write!(
+                f,
+                "The `formation` must map process types to objects with an optional non-negative `quantity`"
+            ),
+        }
+    }
+}
+
+/// Process types scaled up by the `formation` of an `app.json`, see [`Procfile::apply_app_json`]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Formation {
+    /// Process types of the Procfile with a `quantity` that is missing or above zero
+    pub process_types: Vec<String>,
+}
+
+impl Procfile {
+    /// Applies the `formation` of an `app.json`
+    ///
+    /// Process types the formation scales up, with a `quantity` that is missing or above zero,
+    /// are candidates for the default process when there is no `web` process, see
+    /// [`Procfile::default_process_type_with`]. Formation entries without a matching process are
+    /// added as warnings. An `app.json` without a `formation` has an empty formation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid JSON or formation entries, without changing the Procfile.
+    pub fn apply_app_json(&mut self, input: &str) -> Result<Formation, AppJsonError> {
+        let app_json: Value = serde_json::from_str(input)
+            .map_err(|error| AppJsonError::InvalidJson(error.to_string()))?;
+        let entries = match &app_json["formation"] {
+            Value::Null => return Ok(Formation::default()),
+            Value::Object(formation) => formation
+                .iter()
+                .map(|(process_type, entry)| match (entry, &entry["quantity"]) {
+                    (Value::Object(_), Value::Null) => Ok((process_type, 1)),
+                    (Value::Object(_), quantity) => quantity
+                        .as_u64()
+                        .map(|quantity| (process_type, quantity))
+                        .ok_or(AppJsonError::InvalidFormation),
+                    _ => Err(AppJsonError::InvalidFormation),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => return Err(AppJsonError::InvalidFormation),
+        };
+
+        let mut formation = Formation::default();
+        for (process_type, quantity) in entries {
+            if !self.processes.contains_key(process_type) {
+                self.warnings
+                    .push(ProcfileWarning::UnknownFormationProcessType {
+                        process_type: process_type.clone(),
+                    });
+            } else if quantity > 0 {
+                formation.process_types.push(process_type.clone());
+            }
+        }
+        Ok(formation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formation_process_types() {
+        let mut procfile: Procfile = "web: rails s\nworker: sidekiq\nclock: clockwork\n"
+            .parse()
+            .unwrap();
+        let formation = procfile
+            .apply_app_json(
+                r#"{
+                    "name": "example",
+                    "formation": {
+                        "web": { "quantity": 0 },
+                        "worker": { "quantity": 2, "size": "standard-1x" },
+                        "clock": {},
+                        "release": { "quantity": 1 }
+                    }
+                }"#,
+            )
+            .unwrap();
+
+        assert_eq!(vec!["clock", "worker"], formation.process_types);
+        assert_eq!(
+            vec![ProcfileWarning::UnknownFormationProcessType {
+                process_type: String::from("release"),
+            }],
+            procfile.warnings
+        );
+    }
+
+    #[test]
+    fn without_formation() {
+        let mut procfile: Procfile = "web: rails s\n".parse().unwrap();
+        let formation = procfile.apply_app_json(r#"{"name": "example"}"#).unwrap();
+
+        assert_eq!(Formation::default(), formation);
+        assert!(procfile.warnings.is_empty());
+    }
+
+    #[test]
+    fn invalid_app_json() {
+        let mut procfile: Procfile = "web: rails s\n".parse().unwrap();
+        assert!(matches!(
+            procfile.apply_app_json("{"),
+            Err(AppJsonError::InvalidJson(_))
+        ));
+        assert!(matches!(
+            procfile.apply_app_json(r#"{"formation": ["web"]}"#),
+            Err(AppJsonError::InvalidFormation)
+        ));
+        assert!(matches!(
+            procfile.apply_app_json(r#"{"formation": {"release": {}, "web": {"quantity": -1}}}"#),
+            Err(AppJsonError::InvalidFormation)
+        ));
+        assert!(procfile.warnings.is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+//! Steps of the buildpack's build phase, shared with the `procfile` command-line tool
+use crate::{Formation, Procfile, ProcfileBuildpackError, ProcfileWarning};
+use bullet_stream::style;
+use libcnb::data::launch::Launch;
+use std::path::Path;
+
+/// Reads and parses a `Procfile`
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a valid Procfile.
+pub fn read_procfile(path: &Path) -> Result<Procfile, ProcfileBuildpackError> {
+    fs_err::read_to_string(path)
+        .map_err(ProcfileBuildpackError::CannotReadProcfileContents)
+        .and_then(|procfile_contents| parse_procfile(&procfile_contents))
+}
+
+/// Parses the contents of a `Procfile`, for callers that also need the contents
+///
+/// # Errors
+///
+/// Returns an error if the contents are not a valid Procfile.
+pub fn parse_procfile(contents: &str) -> Result<Procfile, ProcfileBuildpackError> {
+    contents
+        .parse()
+        .map_err(ProcfileBuildpackError::ProcfileParsingError)
+}
+
+/// Applies the `formation` of the `app.json` in the app directory, when there is one
+///
+/// The `app.json` may be used by other tools for other purposes, so when it cannot be read or
+/// has an invalid formation it is ignored with a warning instead of failing the build.
+pub fn apply_app_json(app_dir: &Path, procfile: &mut Procfile) -> Formation {
+    let path = app_dir.join("app.json");
+    if !path.exists() {
+        return Formation::default();
+    }
+    let formation = fs_err::read_to_string(path)
+        .map_err(|error| error.to_string())
+        .and_then(|contents| {
+            procfile
+                .apply_app_json(&contents)
+                .map_err(|error| error.to_string())
+        });
+
+    formation.unwrap_or_else(|error| {
+        procfile
+            .warnings
+            .push(ProcfileWarning::InvalidAppJson { error });
+        Formation::default()
+    })
+}
+
+/// Warnings followed by processes, as printed by the buildpack
+#[must_use]
+pub fn procfile_messages(procfile: &Procfile) -> Vec<String> {
+    let warning_prefix = style::important("WARNING:");
+    procfile
+        .warnings
+        .iter()
+        .map(|message| format!("{warning_prefix} {message}"))
+        .chain(
+            procfile
+                .processes
+                .iter()
+                .map(|(name, command)| format!("{name}: {cmd}", cmd = style::command(command))),
+        )
+        .collect()
+}
+
+/// Converts a `Procfile` into CNB launch configuration, see [`apply_app_json`] for the formation
+///
+/// # Errors
+///
+/// Returns an error if a process name is not a valid CNB process type.
+pub fn procfile_launch(
+    procfile: Procfile,
+    formation: &Formation,
+) -> Result<Launch, ProcfileBuildpackError> {
+    procfile
+        .into_launch(formation)
+        .map_err(ProcfileBuildpackError::ProcfileConversionError)
+}
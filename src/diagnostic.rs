@@ -0,0 +1,300 @@
+//! Contains logic for locating problems in the source of a `Procfile`
+use crate::ProcfileWarning;
+use crate::lint::{LINTS, lint_procfile};
+use crate::procfile::line_column;
+use bullet_stream::strip_ansi;
+use std::ops::Range;
+
+/// A problem found in the source of a `Procfile`
+///
+/// Unlike [`ProcfileWarning`], diagnostics point to where in the file the problem is, so that
+/// editors and CI tools can annotate the offending line.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProcfileDiagnostic {
+    pub rule: &'static Rule,
+    pub severity: Severity,
+    /// Plain text description, without ANSI styling
+    pub message: String,
+    /// Byte range of the offending input
+    pub span: Range<usize>,
+    pub start: SourcePosition,
+    pub end: SourcePosition,
+    pub fix: Option<ProcfileFix>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Severity {
+    /// A problem that must be fixed, the buildpack only fails for `P001`
+    Error,
+    /// A problem worth fixing, like the warnings the buildpack prints
+    Warning,
+}
+
+/// A check that produces diagnostics, identified by a stable ID
+#[derive(Debug, Eq, PartialEq)]
+pub struct Rule {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Severity of the diagnostics of this rule
+    pub severity: Severity,
+}
+
+pub(crate) static INVALID_PROCFILE: Rule = Rule {
+    id: "P001",
+    name: "invalid-procfile",
+    description: "The Procfile does not follow the specification and the build fails",
+    severity: Severity::Error,
+};
+
+pub(crate) static CORRECTED_KEY: Rule = Rule {
+    id: "P002",
+    name: "corrected-key",
+    description: "A process type with `_`, uppercase characters or leading spaces is corrected",
+    severity: Severity::Warning,
+};
+
+pub(crate) static DUPLICATE_KEY: Rule = Rule {
+    id: "P003",
+    name: "duplicate-key",
+    description: "A process type is defined more than once and only the last definition is used",
+    severity: Severity::Warning,
+};
+
+pub(crate) static EMPTY_PROCFILE: Rule = Rule {
+    id: "P004",
+    name: "empty-procfile",
+    description: "The Procfile defines no processes",
+    severity: Severity::Warning,
+};
+
+pub(crate) static UNKNOWN_FORMATION_PROCESS: Rule = Rule {
+    id: "P005",
+    name: "unknown-formation-process",
+    description: "A process type in the `app.json` formation is not defined in the Procfile",
+    severity: Severity::Warning,
+};
+
+pub(crate) static INVALID_APP_JSON: Rule = Rule {
+    id: "P006",
+    name: "invalid-app-json",
+    description: "The `app.json` cannot be read or has an invalid formation, so it is ignored",
+    severity: Severity::Warning,
+};
+
+pub(crate) static WEB_PORT: Rule = Rule {
+    id: "P010",
+    name: "web-port",
+    description: "The `web` process does not listen on `$PORT`, or hardcodes a port",
+    severity: Severity::Warning,
+};
+
+pub(crate) static LOCALHOST_BIND: Rule = Rule {
+    id: "P011",
+    name: "localhost-bind",
+    description: "The `web` process only listens on `localhost`, which the platform cannot reach",
+    severity: Severity::Warning,
+};
+
+pub(crate) static DEV_SERVER: Rule = Rule {
+    id: "P012",
+    name: "dev-server",
+    description: "A process runs a development server or mode, which is not meant for production",
+    severity: Severity::Warning,
+};
+
+pub(crate) static HARDCODED_SECRET: Rule = Rule {
+    id: "P013",
+    name: "hardcoded-secret",
+    description: "A command contains a credential, which ends up in the build log and image metadata",
+    severity: Severity::Error,
+};
+
+pub(crate) static BACKGROUND_PROCESS: Rule = Rule {
+    id: "P014",
+    name: "background-process",
+    description: "A command runs in the background or as a daemon, so the process exits or misses signals",
+    severity: Severity::Warning,
+};
+
+pub(crate) static MISSING_EXEC: Rule = Rule {
+    id: "P015",
+    name: "missing-exec",
+    description: "The last command of a compound command is not `exec`'d, so it does not receive signals",
+    severity: Severity::Warning,
+};
+
+/// All rules, ordered by ID
+pub static RULES: &[&Rule] = &[
+    &INVALID_PROCFILE,
+    &CORRECTED_KEY,
+    &DUPLICATE_KEY,
+    &EMPTY_PROCFILE,
+    &UNKNOWN_FORMATION_PROCESS,
+    &INVALID_APP_JSON,
+    &WEB_PORT,
+    &LOCALHOST_BIND,
+    &DEV_SERVER,
+    &HARDCODED_SECRET,
+    &BACKGROUND_PROCESS,
+    &MISSING_EXEC,
+];
+
+/// Rules of the errors and warnings the buildpack reports, other rules are only checked by tools
+pub static BUILDPACK_RULES: &[&Rule] = &[
+    &INVALID_PROCFILE,
+    &CORRECTED_KEY,
+    &DUPLICATE_KEY,
+    &EMPTY_PROCFILE,
+    &UNKNOWN_FORMATION_PROCESS,
+    &INVALID_APP_JSON,
+];
+
+/// The rule of a warning, for reporting warnings without a location
+pub(crate) fn warning_rule(warning: &ProcfileWarning) -> &'static Rule {
+    match warning {
+        ProcfileWarning::KeyCorrected { .. } => &CORRECTED_KEY,
+        ProcfileWarning::DuplicateKey { .. } | ProcfileWarning::MergedDuplicateKey { .. } => {
+            &DUPLICATE_KEY
+        }
+        ProcfileWarning::EmptyFile => &EMPTY_PROCFILE,
+        ProcfileWarning::InSource { warning, .. } => warning_rule(warning),
+        ProcfileWarning::UnknownFormationProcessType { .. } => &UNKNOWN_FORMATION_PROCESS,
+        ProcfileWarning::InvalidAppJson { .. } => &INVALID_APP_JSON,
+    }
+}
+
+/// One-based line and column (in characters) in the source of a `Procfile`
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SourcePosition {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// An edit that resolves a diagnostic
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProcfileFix {
+    pub description: String,
+    /// Byte range of the input that is replaced
+    pub span: Range<usize>,
+    pub replacement: String,
+}
+
+impl ProcfileDiagnostic {
+    /// A diagnostic with the default severity of the rule
+    pub(crate) fn new(input: &str, rule: &'static Rule, message: &str, span: Range<usize>) -> Self {
+        let position = |offset| {
+            let (line, column) = line_column(input, offset);
+            SourcePosition { line, column }
+        };
+        Self {
+            rule,
+            severity: rule.severity,
+            message: strip_ansi(message),
+            start: position(span.start),
+            end: position(span.end),
+            span,
+            fix: None,
+        }
+    }
+}
+
+/// Returns the error or the warnings the buildpack would report for this input, and the
+/// findings of all other lints, located in it
+///
+/// Diagnostics are ordered by their position in the input.
+#[must_use]
+pub fn procfile_diagnostics(input: &str) -> Vec<ProcfileDiagnostic> {
+    lint_procfile(input, LINTS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_procfile_has_no_diagnostics() {
+        assert_eq!(
+            Vec::<ProcfileDiagnostic>::new(),
+            procfile_diagnostics("web: rails s -p $PORT\n# worker\nworker: sidekiq\n")
+        );
+    }
+
+    #[test]
+    fn parse_error_diagnostic() {
+        let diagnostics = procfile_diagnostics("web: rails s\n-web: puma\n");
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!("P001", diagnostics[0].rule.id);
+        assert_eq!(Severity::Error, diagnostics[0].severity);
+        assert_eq!(13..14, diagnostics[0].span);
+        assert_eq!(SourcePosition { line: 2, column: 1 }, diagnostics[0].start);
+        assert_eq!(None, diagnostics[0].fix);
+    }
+
+    #[test]
+    fn corrected_key_diagnostic_with_fix() {
+        let input = "web: rails s -p $PORT\r\n  Worker_1: sidekiq\r\n";
+        let diagnostics = procfile_diagnostics(input);
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(Severity::Warning, diagnostics[0].severity);
+        assert_eq!(
+            "Procfile key `  Worker_1` has been corrected to `worker-1`. Please update your Procfile.",
+            diagnostics[0].message
+        );
+        assert_eq!("  Worker_1", &input[diagnostics[0].span.clone()]);
+        assert_eq!(SourcePosition { line: 2, column: 1 }, diagnostics[0].start);
+        assert_eq!(
+            SourcePosition {
+                line: 2,
+                column: 11
+            },
+            diagnostics[0].end
+        );
+        assert_eq!(
+            Some(ProcfileFix {
+                description: String::from("Rename `Worker_1` to `worker-1`"),
+                span: diagnostics[0].span.clone(),
+                replacement: String::from("worker-1"),
+            }),
+            diagnostics[0].fix
+        );
+    }
+
+    #[test]
+    fn duplicate_key_diagnostics_point_to_overridden_definitions() {
+        let diagnostics = procfile_diagnostics("worker: a\nWORKER: b\nworker: c\n");
+
+        assert_eq!(
+            vec![
+                (1, DUPLICATE_KEY.id),
+                (2, CORRECTED_KEY.id),
+                (2, DUPLICATE_KEY.id),
+            ],
+            diagnostics
+                .iter()
+                .map(|diagnostic| (diagnostic.start.line, diagnostic.rule.id))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn duplicate_key_message() {
+        let diagnostics = procfile_diagnostics("web: a\nweb: b\n");
+
+        assert_eq!(
+            "Duplicate key `web` found. The value `b` will be used.",
+            diagnostics[0].message
+        );
+    }
+
+    #[test]
+    fn empty_file_diagnostic() {
+        let diagnostics = procfile_diagnostics("");
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(0..0, diagnostics[0].span);
+        assert_eq!("Empty file, no processes defined", diagnostics[0].message);
+    }
+}
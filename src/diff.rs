@@ -0,0 +1,261 @@
+//! Contains logic for comparing two `Procfile`s
+use crate::Procfile;
+use std::fmt::Display;
+
+/// Semantic differences between two `Procfile`s
+///
+/// Only processes are compared. Comments, empty lines, key corrections and trailing whitespace
+/// of commands do not result in differences.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ProcfileDiff {
+    pub changes: Vec<ProcfileChange>,
+    /// Processes present in both Procfiles are declared in a different order
+    pub reordered: bool,
+    /// The default process changed, see [`Procfile::default_process_type`]
+    pub default: Option<DefaultProcessChange>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ProcfileChange {
+    Added {
+        key: String,
+        command: String,
+    },
+    Removed {
+        key: String,
+        command: String,
+    },
+    /// A process was removed and another with the same command was added
+    Renamed {
+        from: String,
+        to: String,
+        command: String,
+    },
+    CommandChanged {
+        key: String,
+        from: String,
+        to: String,
+    },
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DefaultProcessChange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+impl ProcfileDiff {
+    /// Returns `true` when both Procfiles define the same processes in the same order
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && !self.reordered && self.default.is_none()
+    }
+}
+
+impl Procfile {
+    /// Compares the processes of this Procfile (old) to another one (new)
+    #[must_use]
+    pub fn diff(&self, other: &Procfile) -> ProcfileDiff {
+        let mut changes = Vec::new();
+        let mut removed = Vec::new();
+        let mut added = Vec::new();
+
+        for (key, command) in &self.processes {
+            match other.processes.get(key) {
+                Some(other_command) if !same_command(command, other_command) => {
+                    changes.push(ProcfileChange::CommandChanged {
+                        key: key.clone(),
+                        from: command.clone(),
+                        to: other_command.clone(),
+                    });
+                }
+                Some(_) => {}
+                None => removed.push((key, command)),
+            }
+        }
+        for (key, command) in &other.processes {
+            if !self.processes.contains_key(key) {
+                added.push((key, command));
+            }
+        }
+
+        for (key, command) in removed {
+            if let Some(index) = added
+                .iter()
+                .position(|(_, other_command)| same_command(command, other_command))
+            {
+                let (other_key, _) = added.remove(index);
+                changes.push(ProcfileChange::Renamed {
+                    from: key.clone(),
+                    to: other_key.clone(),
+                    command: command.clone(),
+                });
+            } else {
+                changes.push(ProcfileChange::Removed {
+                    key: key.clone(),
+                    command: command.clone(),
+                });
+            }
+        }
+        changes.extend(
+            added
+                .into_iter()
+                .map(|(key, command)| ProcfileChange::Added {
+                    key: key.clone(),
+                    command: command.clone(),
+                }),
+        );
+
+        let common_order = |procfile: &Procfile, other: &Procfile| {
+            procfile
+                .processes
+                .keys()
+                .filter(|key| other.processes.contains_key(*key))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        let reordered = common_order(self, other) != common_order(other, self);
+
+        let from = self.default_process_type();
+        let to = other.default_process_type();
+        let default = (from != to).then(|| DefaultProcessChange {
+            from: from.map(ToString::to_string),
+            to: to.map(ToString::to_string),
+        });
+
+        ProcfileDiff {
+            changes,
+            reordered,
+            default,
+        }
+    }
+}
+
+/// Trailing whitespace is not significant to `bash -c`
+fn same_command(a: &str, b: &str) -> bool {
+    a.trim_end() == b.trim_end()
+}
+
+impl Display for ProcfileDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in &self.changes {
+            match change {
+                ProcfileChange::Added { key, command } => writeln!(f, "+ {key}: {command}")?,
+                ProcfileChange::Removed { key, command } => writeln!(f, "- {key}: {command}")?,
+                ProcfileChange::Renamed { from, to, .. } => {
+                    writeln!(f, "~ {from} renamed to {to}")?;
+                }
+                ProcfileChange::CommandChanged { key, from, to } => {
+                    writeln!(f, "~ {key}: {from}")?;
+                    writeln!(f, "  {key}: {to}")?;
+                }
+            }
+        }
+        if self.reordered {
+            writeln!(f, "~ process order changed")?;
+        }
+        if let Some(DefaultProcessChange { from, to }) = &self.default {
+            writeln!(
+                f,
+                "! default process changed from {} to {}",
+                from.as_deref().unwrap_or("none"),
+                to.as_deref().unwrap_or("none")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old: &str, new: &str) -> ProcfileDiff {
+        let old: Procfile = old.parse().unwrap();
+        let new: Procfile = new.parse().unwrap();
+        old.diff(&new)
+    }
+
+    #[test]
+    fn formatting_changes_are_not_differences() {
+        let result = diff(
+            "web: rails s\nworker: sidekiq",
+            "# Processes\n  WEB:   rails s  \n\nworker: sidekiq\n",
+        );
+
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn added_removed_and_changed_processes() {
+        let result = diff(
+            "web: rails s\nworker: sidekiq\nclock: clockwork",
+            "web: puma\nworker: sidekiq\nrelease: rake db:migrate",
+        );
+
+        assert_eq!(
+            vec![
+                ProcfileChange::CommandChanged {
+                    key: "web".to_string(),
+                    from: "rails s".to_string(),
+                    to: "puma".to_string(),
+                },
+                ProcfileChange::Removed {
+                    key: "clock".to_string(),
+                    command: "clockwork".to_string(),
+                },
+                ProcfileChange::Added {
+                    key: "release".to_string(),
+                    command: "rake db:migrate".to_string(),
+                },
+            ],
+            result.changes
+        );
+        assert!(!result.reordered);
+        assert_eq!(None, result.default);
+    }
+
+    #[test]
+    fn renamed_process() {
+        let result = diff("worker: sidekiq", "jobs: sidekiq");
+
+        assert_eq!(
+            vec![ProcfileChange::Renamed {
+                from: "worker".to_string(),
+                to: "jobs".to_string(),
+                command: "sidekiq".to_string(),
+            }],
+            result.changes
+        );
+        assert_eq!(
+            Some(DefaultProcessChange {
+                from: Some("worker".to_string()),
+                to: Some("jobs".to_string()),
+            }),
+            result.default
+        );
+    }
+
+    #[test]
+    fn reordered_processes() {
+        let result = diff(
+            "worker: sidekiq\nweb: rails s",
+            "web: rails s\nworker: sidekiq",
+        );
+
+        assert!(result.changes.is_empty());
+        assert!(result.reordered);
+        assert_eq!(None, result.default);
+        assert_eq!("~ process order changed\n", result.to_string());
+    }
+
+    #[test]
+    fn default_process_change() {
+        let result = diff("worker: sidekiq", "worker: sidekiq\nconsole: irb");
+
+        assert_eq!(
+            "+ console: irb\n! default process changed from worker to none\n",
+            result.to_string()
+        );
+    }
+}
@@ -0,0 +1,477 @@
+//! Contains logic for editing a `Procfile` while preserving its formatting
+use crate::procfile::{is_valid_key, parse_line_key};
+use crate::{Procfile, ProcfileError};
+use std::fmt::Display;
+
+/// A `Procfile` that can be edited without rewriting the whole file
+///
+/// Comments, empty lines and lines that are not touched by an edit are kept byte-identical.
+/// When a key is defined more than once, edits apply to its last definition (the one that takes
+/// precedence), except for [`ProcfileDocument::remove`] which removes all of them.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProcfileDocument {
+    lines: Vec<Line>,
+    line_ending: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Line {
+    text: String,
+    ending: String,
+    key: Option<String>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum ProcfileEditError {
+    UnknownKey(String),
+    DuplicateKey(String),
+    InvalidKey(String),
+    InvalidCommand(String),
+}
+
+impl Display for ProcfileEditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcfileEditError::UnknownKey(key) => write!(f, "No process with key `{key}`"),
+            ProcfileEditError::DuplicateKey(key) => {
+                write!(f, "A process with key `{key}` already exists")
+            }
+            ProcfileEditError::InvalidKey(key) => write!(
+                f,
+                "Invalid key `{key}`, keys must be 1 to 63 lowercase alphanumeric (a-z0-9) or `-` characters and start and end with an alphanumeric character"
+            ),
+            ProcfileEditError::InvalidCommand(command) => write!(
+                f,
+                "Invalid command `{command}`, commands must be a single line with at least one non-whitespace character"
+            ),
+        }
+    }
+}
+
+impl std::str::FromStr for ProcfileDocument {
+    type Err = ProcfileError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        // Reject anything the buildpack would reject, with the same error.
+        input.parse::<Procfile>()?;
+
+        let lines: Vec<Line> = input
+            .split_inclusive('\n')
+            .map(|line| {
+                let (text, ending) = if let Some(text) = line.strip_suffix("\r\n") {
+                    (text, "\r\n")
+                } else if let Some(text) = line.strip_suffix('\n') {
+                    (text, "\n")
+                } else {
+                    (line, "")
+                };
+                Line {
+                    text: text.to_string(),
+                    ending: ending.to_string(),
+                    key: parse_line_key(text),
+                }
+            })
+            .collect();
+
+        let line_ending = lines
+            .iter()
+            .map(|line| line.ending.clone())
+            .find(|ending| !ending.is_empty())
+            .unwrap_or_else(|| String::from("\n"));
+
+        Ok(Self { lines, line_ending })
+    }
+}
+
+impl Display for ProcfileDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            write!(f, "{}{}", line.text, line.ending)?;
+        }
+        Ok(())
+    }
+}
+
+impl ProcfileDocument {
+    /// Parses the edited document into a `Procfile`
+    ///
+    /// # Errors
+    ///
+    /// Edits are validated, so this only fails if the original input was not a valid Procfile.
+    pub fn procfile(&self) -> Result<Procfile, ProcfileError> {
+        self.to_string().parse()
+    }
+
+    /// Rewrites every line into canonical form
+    ///
+    /// Keys are corrected, a single space follows the `:` of each entry, comments start with `#`,
+    /// trailing whitespace is removed and all lines end with the same line ending.
+    pub fn format(&mut self) {
+        for line in &mut self.lines {
+            line.text = match (&line.key, line.text.split_once(':')) {
+                (Some(key), Some((_, value))) => format!(
+                    "{key}: {}",
+                    value.trim_start_matches([' ', '\t']).trim_end()
+                ),
+                _ => line.text.trim().to_string(),
+            };
+            line.ending.clone_from(&self.line_ending);
+        }
+    }
+
+    /// Keys in the order they take effect
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().enumerate().filter_map(|(index, line)| {
+            line.key
+                .as_deref()
+                .filter(|key| self.last_definition(key) == Some(index))
+        })
+    }
+
+    /// Appends a new entry after the last existing entry
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key already exists or either key or command is invalid.
+    pub fn insert(&mut self, key: &str, command: &str) -> Result<(), ProcfileEditError> {
+        let index = self
+            .lines
+            .iter()
+            .rposition(|line| line.key.is_some())
+            .map_or(self.lines.len(), |index| index + 1);
+
+        self.insert_at(index, key, command)
+    }
+
+    /// Inserts a new entry directly before the entry for `anchor`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `anchor` does not exist, the key already exists or either key or
+    /// command is invalid.
+    pub fn insert_before(
+        &mut self,
+        anchor: &str,
+        key: &str,
+        command: &str,
+    ) -> Result<(), ProcfileEditError> {
+        let index = self.require_definition(anchor)?;
+        self.insert_at(index, key, command)
+    }
+
+    /// Removes every definition of `key`, returns `false` if there was none
+    pub fn remove(&mut self, key: &str) -> bool {
+        let mut removed = false;
+        while let Some(index) = self.last_definition(key) {
+            self.remove_line(index);
+            removed = true;
+        }
+        removed
+    }
+
+    /// Renames the process `from` to `to`, keeping its command
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `from` does not exist, `to` already exists or `to` is invalid.
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), ProcfileEditError> {
+        if !is_valid_key(to) {
+            return Err(ProcfileEditError::InvalidKey(to.to_string()));
+        }
+        if from != to && self.last_definition(to).is_some() {
+            return Err(ProcfileEditError::DuplicateKey(to.to_string()));
+        }
+        self.require_definition(from)?;
+
+        for line in &mut self.lines {
+            if line.key.as_deref() == Some(from) {
+                // Keys never contain `:`, so the first one is the delimiter
+                if let Some((_, rest)) = line.text.split_once(':') {
+                    line.text = format!("{to}:{rest}");
+                }
+                line.key = Some(to.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves the entry for `key` directly before the entry for `anchor`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either `key` or `anchor` does not exist.
+    pub fn move_before(&mut self, key: &str, anchor: &str) -> Result<(), ProcfileEditError> {
+        self.move_entry(key, anchor, 0)
+    }
+
+    /// Moves the entry for `key` directly after the entry for `anchor`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either `key` or `anchor` does not exist.
+    pub fn move_after(&mut self, key: &str, anchor: &str) -> Result<(), ProcfileEditError> {
+        self.move_entry(key, anchor, 1)
+    }
+
+    fn move_entry(
+        &mut self,
+        key: &str,
+        anchor: &str,
+        offset: usize,
+    ) -> Result<(), ProcfileEditError> {
+        let from = self.require_definition(key)?;
+        self.require_definition(anchor)?;
+        if key == anchor {
+            return Ok(());
+        }
+
+        let line = self.remove_line(from);
+        let index = self.require_definition(anchor)? + offset;
+        self.insert_line(index, line);
+
+        Ok(())
+    }
+
+    fn insert_at(
+        &mut self,
+        index: usize,
+        key: &str,
+        command: &str,
+    ) -> Result<(), ProcfileEditError> {
+        if !is_valid_key(key) {
+            return Err(ProcfileEditError::InvalidKey(key.to_string()));
+        }
+        if command.trim().is_empty() || command.contains(['\n', '\r']) {
+            return Err(ProcfileEditError::InvalidCommand(command.to_string()));
+        }
+        if self.last_definition(key).is_some() {
+            return Err(ProcfileEditError::DuplicateKey(key.to_string()));
+        }
+
+        self.insert_line(
+            index,
+            Line {
+                text: format!("{key}: {}", command.trim_start()),
+                ending: String::new(),
+                key: Some(key.to_string()),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Inserts a line, keeping a missing line ending at the end of the file
+    fn insert_line(&mut self, index: usize, mut line: Line) {
+        line.ending.clone_from(&self.line_ending);
+        if index == self.lines.len()
+            && let Some(last) = self.lines.last_mut()
+            && last.ending.is_empty()
+        {
+            last.ending.clone_from(&self.line_ending);
+            line.ending = String::new();
+        }
+        self.lines.insert(index, line);
+    }
+
+    /// Removes a line, keeping a missing line ending at the end of the file
+    fn remove_line(&mut self, index: usize) -> Line {
+        let line = self.lines.remove(index);
+        if index == self.lines.len()
+            && line.ending.is_empty()
+            && let Some(last) = self.lines.last_mut()
+        {
+            last.ending = String::new();
+        }
+        line
+    }
+
+    fn last_definition(&self, key: &str) -> Option<usize> {
+        self.lines
+            .iter()
+            .rposition(|line| line.key.as_deref() == Some(key))
+    }
+
+    fn require_definition(&self, key: &str) -> Result<usize, ProcfileEditError> {
+        self.last_definition(key)
+            .ok_or_else(|| ProcfileEditError::UnknownKey(key.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const PROCFILE: &str = indoc! {"
+        # Processes for the app
+        web: bundle exec puma -C config/puma.rb
+
+        # Background jobs
+        worker:   bundle exec sidekiq
+        # Trailing comment
+    "};
+
+    #[test]
+    fn unedited_document_is_byte_identical() {
+        let input = "# Comment\r\n   Web: echo 'web'  \r\n\r\nworker: echo 'worker'";
+        let document: ProcfileDocument = input.parse().unwrap();
+
+        assert_eq!(input, document.to_string());
+        assert_eq!(vec!["web", "worker"], document.keys().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn invalid_document_returns_procfile_error() {
+        assert!("is_w.e.b: echo hello".parse::<ProcfileDocument>().is_err());
+    }
+
+    #[test]
+    fn insert_appends_after_last_entry() {
+        let mut document: ProcfileDocument = PROCFILE.parse().unwrap();
+        document.insert("release", "rake db:migrate").unwrap();
+
+        assert_eq!(
+            indoc! {"
+                # Processes for the app
+                web: bundle exec puma -C config/puma.rb
+
+                # Background jobs
+                worker:   bundle exec sidekiq
+                release: rake db:migrate
+                # Trailing comment
+            "},
+            document.to_string()
+        );
+    }
+
+    #[test]
+    fn insert_keeps_missing_trailing_newline() {
+        let mut document: ProcfileDocument = "web: rails s".parse().unwrap();
+        document.insert("worker", "sidekiq").unwrap();
+
+        assert_eq!("web: rails s\nworker: sidekiq", document.to_string());
+    }
+
+    #[test]
+    fn insert_before_anchor() {
+        let mut document: ProcfileDocument = PROCFILE.parse().unwrap();
+        document
+            .insert_before("web", "release", "rake db:migrate")
+            .unwrap();
+
+        assert_eq!(
+            vec!["release", "web", "worker"],
+            document.keys().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Err(ProcfileEditError::UnknownKey("console".to_string())),
+            document.insert_before("console", "clock", "clockwork")
+        );
+    }
+
+    #[test]
+    fn insert_rejects_invalid_entries() {
+        let mut document: ProcfileDocument = PROCFILE.parse().unwrap();
+
+        assert_eq!(
+            Err(ProcfileEditError::DuplicateKey("web".to_string())),
+            document.insert("web", "rails s")
+        );
+        assert_eq!(
+            Err(ProcfileEditError::InvalidKey("Bad_Key".to_string())),
+            document.insert("Bad_Key", "rails s")
+        );
+        assert_eq!(
+            Err(ProcfileEditError::InvalidCommand("  ".to_string())),
+            document.insert("console", "  ")
+        );
+        assert_eq!(
+            Err(ProcfileEditError::InvalidCommand("a\nb".to_string())),
+            document.insert("console", "a\nb")
+        );
+        assert_eq!(PROCFILE, document.to_string());
+    }
+
+    #[test]
+    fn remove_all_definitions() {
+        let mut document: ProcfileDocument = "web: rails s\nworker: a\n# Keep me\nworker: b"
+            .parse()
+            .unwrap();
+
+        assert!(document.remove("worker"));
+        assert!(!document.remove("worker"));
+        assert_eq!("web: rails s\n# Keep me", document.to_string());
+    }
+
+    #[test]
+    fn rename_keeps_command_and_formatting() {
+        let mut document: ProcfileDocument = PROCFILE.parse().unwrap();
+        document.rename("worker", "jobs").unwrap();
+
+        assert_eq!(
+            indoc! {"
+                # Processes for the app
+                web: bundle exec puma -C config/puma.rb
+
+                # Background jobs
+                jobs:   bundle exec sidekiq
+                # Trailing comment
+            "},
+            document.to_string()
+        );
+        assert_eq!(
+            Err(ProcfileEditError::DuplicateKey("web".to_string())),
+            document.rename("jobs", "web")
+        );
+    }
+
+    #[test]
+    fn rename_corrected_key() {
+        let mut document: ProcfileDocument = "  Web_Server: rails s\n".parse().unwrap();
+        document.rename("web-server", "web").unwrap();
+
+        assert_eq!("web: rails s\n", document.to_string());
+        assert!(document.procfile().unwrap().warnings.is_empty());
+    }
+
+    #[test]
+    fn move_entries() {
+        let mut document: ProcfileDocument = PROCFILE.parse().unwrap();
+        document.move_before("worker", "web").unwrap();
+
+        assert_eq!(
+            indoc! {"
+                # Processes for the app
+                worker:   bundle exec sidekiq
+                web: bundle exec puma -C config/puma.rb
+
+                # Background jobs
+                # Trailing comment
+            "},
+            document.to_string()
+        );
+
+        document.move_after("worker", "web").unwrap();
+        assert_eq!(vec!["web", "worker"], document.keys().collect::<Vec<_>>());
+        assert_eq!(
+            Err(ProcfileEditError::UnknownKey("console".to_string())),
+            document.move_after("console", "web")
+        );
+    }
+
+    #[test]
+    fn format_document() {
+        let mut document: ProcfileDocument =
+            "  # Processes  \r\n   Web_Server:bundle exec puma   \r\n \t\r\nworker:\t  sidekiq\n# Done"
+                .parse()
+                .unwrap();
+        document.format();
+
+        assert_eq!(
+            "# Processes\r\nweb-server: bundle exec puma\r\n\r\nworker: sidekiq\r\n# Done\r\n",
+            document.to_string()
+        );
+        assert!(document.procfile().unwrap().warnings.is_empty());
+    }
+}
@@ -1,17 +1,27 @@
-use crate::launch::ProcfileConversionError;
-use crate::procfile::ProcfileError;
+use crate::{HerokuYmlError, ProcfileConversionError, ProcfileError};
 use bullet_stream::Print;
 use indoc::formatdoc;
+use std::io::Write;
 
 #[derive(Debug)]
-pub(crate) enum ProcfileBuildpackError {
+pub enum ProcfileBuildpackError {
     CannotReadProcfileContents(std::io::Error),
     ProcfileParsingError(ProcfileError),
     ProcfileConversionError(ProcfileConversionError),
+    CannotReadHerokuYmlContents(std::io::Error),
+    HerokuYmlParsingError(HerokuYmlError),
 }
 
-pub(crate) fn error_handler(buildpack_error: ProcfileBuildpackError) {
-    let build_output = Print::new(std::io::stdout()).without_header();
+pub fn error_handler(buildpack_error: ProcfileBuildpackError) {
+    print_error(std::io::stdout(), buildpack_error);
+}
+
+/// Prints the error like [`error_handler`], to another output like stderr
+pub fn print_error<W>(output: W, buildpack_error: ProcfileBuildpackError)
+where
+    W: Write + Send + Sync + 'static,
+{
+    let build_output = Print::new(output).without_header();
     match buildpack_error {
         ProcfileBuildpackError::CannotReadProcfileContents(io_error) => {
             build_output.error(formatdoc! {"
@@ -34,6 +44,28 @@ pub(crate) fn error_handler(buildpack_error: ProcfileBuildpackError) {
                 {parsing_error}
             "});
         }
+        ProcfileBuildpackError::CannotReadHerokuYmlContents(io_error) => {
+            build_output.error(formatdoc! {"
+                Cannot read heroku.yml contents
+
+                Please ensure the heroku.yml in the root of your application is a readable UTF-8
+                encoded file and try again.
+
+                Underlying cause was: {io_error}
+            "});
+        }
+        ProcfileBuildpackError::HerokuYmlParsingError(heroku_yml_error) => {
+            build_output.error(formatdoc! {"
+                Invalid heroku.yml `run` section
+
+                Without a `Procfile`, processes are read from the `run` section of `heroku.yml`,
+                which contains an invalid entry and the buildpack cannot continue.
+
+                To fix this problem please correct the following error and commit the results to git:
+
+                {heroku_yml_error}
+            "});
+        }
         ProcfileBuildpackError::ProcfileConversionError(conversion_error) => match conversion_error
         {
             ProcfileConversionError::InvalidProcessType(libcnb_error) => {
@@ -0,0 +1,86 @@
+//! Contains logic for exporting Procfile processes to other process managers
+mod compose;
+mod kubernetes;
+mod systemd;
+
+pub use compose::{ComposeOptions, compose_file};
+pub use kubernetes::{KubernetesOptions, kubernetes_manifests};
+pub use systemd::{SystemdOptions, systemd_units};
+
+use std::fmt::Display;
+
+/// A file generated by an exporter
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ExportedFile {
+    pub name: String,
+    pub contents: String,
+}
+
+/// An error exporting Procfile processes
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ExportError {
+    /// The ports of the processes, 100 apart, do not fit in the port range
+    PortsOutOfRange { first: u16, count: usize },
+    /// The app name cannot be used to name the exported resources
+    InvalidAppName {
+        app: String,
+        requirement: &'static str,
+    },
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::PortsOutOfRange { first, count } => write!(
+                f,
+                "Cannot assign ports to {count} processes starting at {first}. Each process gets a port 100 higher than the previous one and the highest port is 65535."
+            ),
+            ExportError::InvalidAppName { app, requirement } => {
+                write!(f, "The app name `{app}` is not valid, it {requirement}.")
+            }
+        }
+    }
+}
+
+/// The `PORT` of `count` processes, starting at `first` and incremented by 100 per process
+///
+/// Returns `None` when they don't fit in the port range.
+#[must_use]
+pub fn process_ports(first: u16, count: usize) -> Option<Vec<u16>> {
+    (0..count)
+        .map(|index| {
+            u16::try_from(index)
+                .ok()?
+                .checked_mul(100)?
+                .checked_add(first)
+        })
+        .collect()
+}
+
+/// Indents each line by `width` spaces
+fn indent(lines: &str, width: usize) -> String {
+    lines
+        .lines()
+        .map(|line| format!("{:width$}{line}", ""))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Quotes a YAML scalar as a double-quoted string
+fn yaml_string(value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ports_within_port_range() {
+        assert_eq!(Some(vec![5000, 5100, 5200]), process_ports(5000, 3));
+        assert_eq!(Some(vec![65435, 65535]), process_ports(65435, 2));
+        assert_eq!(None, process_ports(65500, 2));
+        assert_eq!(Some(Vec::new()), process_ports(65535, 0));
+    }
+}
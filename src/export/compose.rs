@@ -0,0 +1,155 @@
+use super::{ExportError, ExportedFile, indent, yaml_string};
+use crate::Procfile;
+use indoc::formatdoc;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub struct ComposeOptions {
+    /// Name of the Compose project, lowercase letters, digits, `-` and `_`
+    pub app: String,
+    /// Reference of the image built with the buildpack
+    pub image: String,
+    /// `PORT` of the `web` process, published on the same host port
+    pub port: u16,
+    pub env_file: Option<PathBuf>,
+}
+
+/// Generates a `compose.yaml` with a service per process
+///
+/// Services start with the CNB launcher entrypoint for their process type (`/cnb/process/<type>`).
+///
+/// # Errors
+///
+/// Returns an error when the app name is not a valid Compose project name.
+pub fn compose_file(
+    procfile: &Procfile,
+    options: &ComposeOptions,
+) -> Result<ExportedFile, ExportError> {
+    if !is_project_name(&options.app) {
+        return Err(ExportError::InvalidAppName {
+            app: options.app.clone(),
+            requirement: "must only contain lowercase letters, digits, `-` and `_`, starting with a letter or digit",
+        });
+    }
+    let mut contents = format!("name: {}\nservices:\n", yaml_string(&options.app));
+
+    for process_type in procfile.processes.keys() {
+        let mut service = formatdoc! {"
+            image: {image}
+            entrypoint:
+              - /cnb/process/{process_type}
+            ",
+            image = yaml_string(&options.image),
+        };
+        if let Some(env_file) = &options.env_file {
+            service.push_str(&formatdoc! {"
+                env_file:
+                  - {env_file}
+                ",
+                env_file = yaml_string(&env_file.to_string_lossy()),
+            });
+        }
+        if process_type == "web" {
+            service.push_str(&formatdoc! {"
+                environment:
+                  PORT: \"{port}\"
+                ports:
+                  - \"{port}:{port}\"
+                ",
+                port = options.port,
+            });
+        }
+
+        let _ = writeln!(
+            contents,
+            "  {}:\n{}",
+            yaml_string(process_type),
+            indent(&service, 4)
+        );
+    }
+
+    Ok(ExportedFile {
+        name: String::from("compose.yaml"),
+        contents,
+    })
+}
+
+/// Returns `true` for a valid Compose project name
+fn is_project_name(value: &str) -> bool {
+    value.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_compose_file() {
+        let procfile: Procfile = "web: bundle exec puma -p $PORT\nworker: sidekiq"
+            .parse()
+            .unwrap();
+        let file = compose_file(
+            &procfile,
+            &ComposeOptions {
+                app: String::from("myapp"),
+                image: String::from("myapp:latest"),
+                port: 5000,
+                env_file: Some(PathBuf::from(".env")),
+            },
+        )
+        .unwrap();
+
+        assert_eq!("compose.yaml", file.name);
+        assert_eq!(
+            indoc! {r#"
+                name: "myapp"
+                services:
+                  "web":
+                    image: "myapp:latest"
+                    entrypoint:
+                      - /cnb/process/web
+                    env_file:
+                      - ".env"
+                    environment:
+                      PORT: "5000"
+                    ports:
+                      - "5000:5000"
+                  "worker":
+                    image: "myapp:latest"
+                    entrypoint:
+                      - /cnb/process/worker
+                    env_file:
+                      - ".env"
+            "#},
+            file.contents
+        );
+    }
+
+    #[test]
+    fn test_compose_file_invalid_app() {
+        let procfile: Procfile = "web: rails s".parse().unwrap();
+
+        for app in ["My App", "my.app", "_app", ""] {
+            assert!(
+                matches!(
+                    compose_file(
+                        &procfile,
+                        &ComposeOptions {
+                            app: app.to_string(),
+                            image: String::from("myapp:latest"),
+                            port: 5000,
+                            env_file: None,
+                        }
+                    ),
+                    Err(ExportError::InvalidAppName { .. })
+                ),
+                "{app}"
+            );
+        }
+    }
+}
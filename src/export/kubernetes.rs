@@ -0,0 +1,281 @@
+use super::{ExportError, ExportedFile, indent, yaml_string};
+use crate::Procfile;
+use indoc::formatdoc;
+
+const DNS_LABEL_MAX_LENGTH: usize = 63;
+
+#[derive(Debug, Clone)]
+pub struct KubernetesOptions {
+    /// Prefix of the resource names, also used as the `app.kubernetes.io/name` label
+    ///
+    /// Must be a DNS label, so it is a valid name and label value.
+    pub app: String,
+    /// Reference of the image built with the buildpack, for example `registry.example.com/myapp:1.0`
+    pub image: String,
+    /// `PORT` of the `web` process, exposed by its Service
+    pub port: u16,
+}
+
+/// Generates a Deployment manifest per process, and a Service for the `web` process
+///
+/// Resources are named `<app>-<type>`, with `<app>` shortened when needed to keep the name within
+/// the 63 characters of a DNS label. Containers start with the CNB launcher entrypoint for their
+/// process type (`/cnb/process/<type>`).
+///
+/// # Errors
+///
+/// Returns an error when the app name is not a DNS label of at most 63 characters.
+pub fn kubernetes_manifests(
+    procfile: &Procfile,
+    options: &KubernetesOptions,
+) -> Result<Vec<ExportedFile>, ExportError> {
+    let KubernetesOptions { app, image, port } = options;
+    if !is_dns_label(app) {
+        return Err(ExportError::InvalidAppName {
+            app: app.clone(),
+            requirement: "must be at most 63 lowercase letters, digits or `-`, starting and ending with a letter or digit",
+        });
+    }
+
+    Ok(procfile
+        .processes
+        .keys()
+        .map(|process_type| {
+            let name = resource_name(app, process_type);
+            let labels = formatdoc! {"
+                app.kubernetes.io/name: {app}
+                app.kubernetes.io/component: {component}",
+                app = yaml_string(app),
+                component = yaml_string(process_type),
+            };
+            let mut contents = formatdoc! {"
+                apiVersion: apps/v1
+                kind: Deployment
+                metadata:
+                  name: {name}
+                  labels:
+                {metadata_labels}
+                spec:
+                  replicas: 1
+                  selector:
+                    matchLabels:
+                {selector_labels}
+                  template:
+                    metadata:
+                      labels:
+                {template_labels}
+                    spec:
+                      containers:
+                        - name: {container}
+                          image: {image}
+                          command:
+                            - /cnb/process/{process_type}
+                ",
+                metadata_labels = indent(&labels, 4),
+                selector_labels = indent(&labels, 6),
+                template_labels = indent(&labels, 8),
+                container = yaml_string(process_type),
+                image = yaml_string(image),
+            };
+
+            if process_type == "web" {
+                let container_settings = formatdoc! {"
+                    env:
+                      - name: PORT
+                        value: \"{port}\"
+                    ports:
+                      - name: http
+                        containerPort: {port}"
+                };
+                contents.push_str(&indent(&container_settings, 10));
+                contents.push_str(&formatdoc! {"
+
+                    ---
+                    apiVersion: v1
+                    kind: Service
+                    metadata:
+                      name: {name}
+                      labels:
+                    {metadata_labels}
+                    spec:
+                      selector:
+                    {selector_labels}
+                      ports:
+                        - name: http
+                          port: 80
+                          targetPort: http
+                    ",
+                    metadata_labels = indent(&labels, 4),
+                    selector_labels = indent(&labels, 4),
+                });
+            }
+
+            ExportedFile {
+                name: format!("{name}.yaml"),
+                contents,
+            }
+        })
+        .collect())
+}
+
+/// Returns `true` for a DNS label (RFC 1123), as required for names and label values
+fn is_dns_label(value: &str) -> bool {
+    value.len() <= DNS_LABEL_MAX_LENGTH
+        && value.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && value.ends_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Returns `<app>-<type>`, shortening `<app>` to fit in a 63 character DNS label
+///
+/// Process types are at most 63 characters, the type is kept whole so names stay unique.
+fn resource_name(app: &str, process_type: &str) -> String {
+    let available = DNS_LABEL_MAX_LENGTH.saturating_sub(process_type.chars().count() + 1);
+    let prefix = app.chars().take(available).collect::<String>();
+    let prefix = prefix.trim_end_matches('-');
+
+    if prefix.is_empty() {
+        process_type.to_string()
+    } else {
+        format!("{prefix}-{process_type}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn options() -> KubernetesOptions {
+        KubernetesOptions {
+            app: String::from("myapp"),
+            image: String::from("registry.example.com/myapp:1.0"),
+            port: 5000,
+        }
+    }
+
+    #[test]
+    fn test_kubernetes_manifests() {
+        let procfile: Procfile = "web: bundle exec puma -p $PORT\nworker: sidekiq"
+            .parse()
+            .unwrap();
+        let files = kubernetes_manifests(&procfile, &options()).unwrap();
+
+        assert_eq!(
+            vec!["myapp-web.yaml", "myapp-worker.yaml"],
+            files
+                .iter()
+                .map(|file| file.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            indoc! {r#"
+                apiVersion: apps/v1
+                kind: Deployment
+                metadata:
+                  name: myapp-web
+                  labels:
+                    app.kubernetes.io/name: "myapp"
+                    app.kubernetes.io/component: "web"
+                spec:
+                  replicas: 1
+                  selector:
+                    matchLabels:
+                      app.kubernetes.io/name: "myapp"
+                      app.kubernetes.io/component: "web"
+                  template:
+                    metadata:
+                      labels:
+                        app.kubernetes.io/name: "myapp"
+                        app.kubernetes.io/component: "web"
+                    spec:
+                      containers:
+                        - name: "web"
+                          image: "registry.example.com/myapp:1.0"
+                          command:
+                            - /cnb/process/web
+                          env:
+                            - name: PORT
+                              value: "5000"
+                          ports:
+                            - name: http
+                              containerPort: 5000
+                ---
+                apiVersion: v1
+                kind: Service
+                metadata:
+                  name: myapp-web
+                  labels:
+                    app.kubernetes.io/name: "myapp"
+                    app.kubernetes.io/component: "web"
+                spec:
+                  selector:
+                    app.kubernetes.io/name: "myapp"
+                    app.kubernetes.io/component: "web"
+                  ports:
+                    - name: http
+                      port: 80
+                      targetPort: http
+            "#},
+            files[0].contents
+        );
+    }
+
+    #[test]
+    fn test_kubernetes_manifests_without_web() {
+        let procfile: Procfile = "worker: sidekiq".parse().unwrap();
+        let files = kubernetes_manifests(&procfile, &options()).unwrap();
+
+        assert_eq!(1, files.len());
+        assert!(files[0].contents.contains("- /cnb/process/worker\n"));
+        assert!(!files[0].contents.contains("kind: Service"));
+        assert!(!files[0].contents.contains("PORT"));
+    }
+
+    #[test]
+    fn test_kubernetes_manifests_invalid_app() {
+        let procfile: Procfile = "web: rails s".parse().unwrap();
+
+        for app in ["My_App", "my app", "-app", "app-", "", &"a".repeat(64)] {
+            assert!(
+                matches!(
+                    kubernetes_manifests(
+                        &procfile,
+                        &KubernetesOptions {
+                            app: app.to_string(),
+                            ..options()
+                        }
+                    ),
+                    Err(ExportError::InvalidAppName { .. })
+                ),
+                "{app}"
+            );
+        }
+        assert!(
+            kubernetes_manifests(
+                &procfile,
+                &KubernetesOptions {
+                    app: "a".repeat(63),
+                    ..options()
+                }
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_resource_name_fits_dns_label() {
+        assert_eq!("myapp-web", resource_name("myapp", "web"));
+
+        let app = "a".repeat(70);
+        let name = resource_name(&app, "worker");
+        assert_eq!(63, name.len());
+        assert!(name.ends_with("a-worker"));
+
+        assert_eq!("web", resource_name("------", "web"));
+        let process_type = "w".repeat(63);
+        assert_eq!(process_type, resource_name("myapp", &process_type));
+    }
+}
@@ -0,0 +1,210 @@
+use super::{ExportError, ExportedFile, process_ports};
+use crate::Procfile;
+use indoc::formatdoc;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub struct SystemdOptions {
+    /// Prefix of the unit names, the target is named `{app}.target`
+    pub app: String,
+    pub user: Option<String>,
+    pub working_directory: PathBuf,
+    pub env_file: Option<PathBuf>,
+    /// Value of `Restart=`, for example `on-failure` or `always`
+    pub restart: String,
+    /// `PORT` of the first process, following processes get a port 100 higher
+    pub port: u16,
+}
+
+/// Generates a `.service` unit per process and a `.target` unit that groups them
+///
+/// Like the buildpack, each command is run with `bash -c`.
+///
+/// # Errors
+///
+/// Returns an error when the ports of the processes do not fit in the port range.
+pub fn systemd_units(
+    procfile: &Procfile,
+    options: &SystemdOptions,
+) -> Result<Vec<ExportedFile>, ExportError> {
+    let app = &options.app;
+    let mut files = Vec::new();
+    let ports = process_ports(options.port, procfile.processes.len()).ok_or(
+        ExportError::PortsOutOfRange {
+            first: options.port,
+            count: procfile.processes.len(),
+        },
+    )?;
+
+    for ((process_type, command), port) in procfile.processes.iter().zip(ports) {
+        let mut settings = String::new();
+        if let Some(user) = &options.user {
+            let _ = writeln!(settings, "User={user}");
+        }
+        if let Some(env_file) = &options.env_file {
+            let _ = writeln!(settings, "EnvironmentFile={}", env_file.display());
+        }
+
+        files.push(ExportedFile {
+            name: format!("{app}-{process_type}.service"),
+            contents: formatdoc! {"
+                [Unit]
+                Description={app} {process_type} process
+                PartOf={app}.target
+
+                [Service]
+                Type=simple
+                WorkingDirectory={working_directory}
+                {settings}Environment=PORT={port}
+                ExecStart=/bin/bash -c {command}
+                Restart={restart}
+
+                [Install]
+                WantedBy={app}.target
+                ",
+                working_directory = options.working_directory.display(),
+                command = quote(command),
+                restart = options.restart,
+            },
+        });
+    }
+
+    let services = procfile
+        .processes
+        .keys()
+        .map(|process_type| format!("{app}-{process_type}.service"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    files.push(ExportedFile {
+        name: format!("{app}.target"),
+        contents: formatdoc! {"
+            [Unit]
+            Description={app}
+            Wants={services}
+
+            [Install]
+            WantedBy=multi-user.target
+        "},
+    });
+
+    Ok(files)
+}
+
+/// Quotes an argument for `ExecStart=`
+///
+/// Escapes `%` and `$` so that specifiers and variables are left for `bash` to expand.
+fn quote(argument: &str) -> String {
+    let escaped = argument
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('$', "$$");
+    format!("\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn options() -> SystemdOptions {
+        SystemdOptions {
+            app: String::from("myapp"),
+            user: Some(String::from("deploy")),
+            working_directory: PathBuf::from("/srv/myapp"),
+            env_file: Some(PathBuf::from("/etc/myapp.env")),
+            restart: String::from("on-failure"),
+            port: 5000,
+        }
+    }
+
+    #[test]
+    fn test_systemd_units() {
+        let procfile: Procfile = "web: bundle exec puma -p $PORT\nworker: echo \"100%\" \\\\o/"
+            .parse()
+            .unwrap();
+        let files = systemd_units(&procfile, &options()).unwrap();
+
+        assert_eq!(
+            vec!["myapp-web.service", "myapp-worker.service", "myapp.target"],
+            files
+                .iter()
+                .map(|file| file.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            indoc! {r#"
+                [Unit]
+                Description=myapp web process
+                PartOf=myapp.target
+
+                [Service]
+                Type=simple
+                WorkingDirectory=/srv/myapp
+                User=deploy
+                EnvironmentFile=/etc/myapp.env
+                Environment=PORT=5000
+                ExecStart=/bin/bash -c "bundle exec puma -p $$PORT"
+                Restart=on-failure
+
+                [Install]
+                WantedBy=myapp.target
+            "#},
+            files[0].contents
+        );
+        assert!(
+            files[1]
+                .contents
+                .contains(r#"ExecStart=/bin/bash -c "echo \"100%%\" \\\\o/""#)
+        );
+        assert!(files[1].contents.contains("Environment=PORT=5100"));
+        assert_eq!(
+            indoc! {"
+                [Unit]
+                Description=myapp
+                Wants=myapp-web.service myapp-worker.service
+
+                [Install]
+                WantedBy=multi-user.target
+            "},
+            files[2].contents
+        );
+    }
+
+    #[test]
+    fn test_systemd_units_without_optional_settings() {
+        let procfile: Procfile = "web: rails s".parse().unwrap();
+        let files = systemd_units(
+            &procfile,
+            &SystemdOptions {
+                user: None,
+                env_file: None,
+                ..options()
+            },
+        )
+        .unwrap();
+
+        assert!(!files[0].contents.contains("User="));
+        assert!(!files[0].contents.contains("EnvironmentFile="));
+    }
+
+    #[test]
+    fn test_systemd_units_ports_out_of_range() {
+        let procfile: Procfile = "web: rails s\nworker: sidekiq".parse().unwrap();
+
+        assert_eq!(
+            Err(ExportError::PortsOutOfRange {
+                first: 65500,
+                count: 2
+            }),
+            systemd_units(
+                &procfile,
+                &SystemdOptions {
+                    port: 65500,
+                    ..options()
+                }
+            )
+        );
+    }
+}
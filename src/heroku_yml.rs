@@ -0,0 +1,240 @@
+//! Contains logic for reading processes from the `run` section of `heroku.yml`
+use crate::{Procfile, ProcfileError};
+use std::fmt::{Display, Write};
+use yaml_rust2::{Yaml, YamlLoader};
+
+#[derive(Debug)]
+pub enum HerokuYmlError {
+    /// The file is not valid YAML
+    InvalidYaml(String),
+    /// The `run` section is not a mapping of process types to commands
+    InvalidRunSection,
+    /// The command of a process is not a single line string, or a `command` list of them
+    InvalidCommand(String),
+    /// A process type starts with `#` or contains `:` or a line break, which cannot be written as
+    /// a Procfile key
+    UnsupportedProcessType(String),
+    /// A process type is not valid, like it would not be in a `Procfile`
+    InvalidProcessType { key: String, message: String },
+}
+
+impl Display for HerokuYmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HerokuYmlError::InvalidYaml(error) => write!(f, "Invalid YAML: {error}"),
+            HerokuYmlError::InvalidRunSection => {
+                write!(f, "The `run` section must map process types to commands")
+            }
+            HerokuYmlError::InvalidCommand(key) => write!(
+                f,
+                "The command of process `{key}` must be a single line string or have a `command` list of them"
+            ),
+            HerokuYmlError::UnsupportedProcessType(key) => write!(
+                f,
+                "Process type `{key}` must not start with `#` or contain `:` or line breaks"
+            ),
+            HerokuYmlError::InvalidProcessType { key, message } => {
+                write!(f, "Process type `{key}` is not valid: {message}")
+            }
+        }
+    }
+}
+
+impl Procfile {
+    /// Reads the processes from the `run` section of a `heroku.yml`
+    ///
+    /// Returns `None` when there is no `run` section. A process is either a command string or a
+    /// mapping with a `command` string or list. A list with a single item is a command string,
+    /// the items of longer lists are arguments, which are shell quoted and joined with spaces.
+    /// Other settings, like `image`, are ignored.
+    ///
+    /// Process types are validated and corrected exactly like Procfile keys, with the same
+    /// warnings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid YAML, commands and process types.
+    pub fn from_heroku_yml(input: &str) -> Result<Option<Procfile>, HerokuYmlError> {
+        let documents = YamlLoader::load_from_str(input)
+            .map_err(|error| HerokuYmlError::InvalidYaml(error.to_string()))?;
+        let run = match documents.first().map(|document| &document["run"]) {
+            None | Some(Yaml::BadValue | Yaml::Null) => return Ok(None),
+            Some(Yaml::Hash(run)) => run,
+            Some(_) => return Err(HerokuYmlError::InvalidRunSection),
+        };
+
+        // Writing the entries as a Procfile gives them the same validation and warnings
+        let mut procfile = String::new();
+        let mut keys = Vec::new();
+        for (key, value) in run {
+            let key = yaml_scalar(key).ok_or(HerokuYmlError::InvalidRunSection)?;
+            // A key starting with `#` would be written as a comment and silently dropped
+            if key.contains([':', '\r', '\n']) || key.trim_start().starts_with('#') {
+                return Err(HerokuYmlError::UnsupportedProcessType(key));
+            }
+            let command = match value {
+                Yaml::Hash(_) => match &value["command"] {
+                    // A single item is a command string, like in Heroku's documentation
+                    Yaml::Array(words) if words.len() == 1 => {
+                        words[0].as_str().map(ToString::to_string)
+                    }
+                    Yaml::Array(words) => words
+                        .iter()
+                        .map(|word| word.as_str().map(shell_quote))
+                        .collect::<Option<Vec<_>>>()
+                        .map(|words| words.join(" ")),
+                    command => command.as_str().map(ToString::to_string),
+                },
+                command => command.as_str().map(ToString::to_string),
+            }
+            .filter(|command| !command.trim().is_empty() && !command.contains(['\r', '\n']))
+            .ok_or_else(|| HerokuYmlError::InvalidCommand(key.clone()))?;
+            let _ = writeln!(procfile, "{key}: {command}");
+            keys.push(key);
+        }
+
+        // Each process is written on its own line, so the line of an error is its process
+        procfile.parse().map(Some).map_err(|error| {
+            let ProcfileError::ParseError(error) = error;
+            HerokuYmlError::InvalidProcessType {
+                key: keys.swap_remove(error.line() - 1),
+                message: error.message().to_string(),
+            }
+        })
+    }
+}
+
+/// Quotes an argument for `bash`, unless it only contains characters that need no quoting
+///
+/// `$` is not quoted, so arguments like `$PORT` are still expanded.
+fn shell_quote(word: &str) -> String {
+    if !word.is_empty()
+        && word
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || "@%+=:,./_-$".contains(char))
+    {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// A mapping key as a string, YAML keys like `1` or `true` are not strings
+fn yaml_scalar(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::String(value) | Yaml::Real(value) => Some(value.clone()),
+        Yaml::Integer(value) => Some(value.to_string()),
+        Yaml::Boolean(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProcfileWarning;
+    use indoc::indoc;
+
+    #[test]
+    fn run_section_processes() {
+        let procfile = Procfile::from_heroku_yml(indoc! {"
+            build:
+              docker:
+                web: Dockerfile
+            run:
+              web: bundle exec puma -C config/puma.rb
+              Worker_1:
+                command:
+                  - python myworker.py
+                image: web
+              clock:
+                command: bin/clock --verbose
+        "})
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                ("web", "bundle exec puma -C config/puma.rb"),
+                ("worker-1", "python myworker.py"),
+                ("clock", "bin/clock --verbose"),
+            ],
+            procfile
+                .processes
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![ProcfileWarning::KeyCorrected {
+                original: String::from("Worker_1"),
+                fixed: String::from("worker-1"),
+            }],
+            procfile.warnings
+        );
+    }
+
+    #[test]
+    fn without_run_section() {
+        assert!(
+            Procfile::from_heroku_yml("build:\n  docker:\n    web: Dockerfile\n")
+                .unwrap()
+                .is_none()
+        );
+        assert!(Procfile::from_heroku_yml("").unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_entries() {
+        assert!(matches!(
+            Procfile::from_heroku_yml("run: [web]"),
+            Err(HerokuYmlError::InvalidRunSection)
+        ));
+        assert!(matches!(
+            Procfile::from_heroku_yml("run:\n  web: |\n    one\n    two\n"),
+            Err(HerokuYmlError::InvalidCommand(key)) if key == "web"
+        ));
+        assert!(matches!(
+            Procfile::from_heroku_yml("run:\n  'web:1': rails s\n"),
+            Err(HerokuYmlError::UnsupportedProcessType(key)) if key == "web:1"
+        ));
+        assert!(matches!(
+            Procfile::from_heroku_yml("run:\n  '#web': rails s\n  worker: sidekiq\n"),
+            Err(HerokuYmlError::UnsupportedProcessType(key)) if key == "#web"
+        ));
+        assert!(matches!(
+            Procfile::from_heroku_yml("run:\n  ' #web': rails s\n"),
+            Err(HerokuYmlError::UnsupportedProcessType(key)) if key == " #web"
+        ));
+        assert!(matches!(
+            Procfile::from_heroku_yml("run:\n  web: rails s\n  web-: puma\n"),
+            Err(HerokuYmlError::InvalidProcessType { key, .. }) if key == "web-"
+        ));
+        assert!(matches!(
+            Procfile::from_heroku_yml("run:\n  web: [rails s\n"),
+            Err(HerokuYmlError::InvalidYaml(_))
+        ));
+    }
+
+    #[test]
+    fn command_lists_are_shell_quoted() {
+        let procfile = Procfile::from_heroku_yml(indoc! {r#"
+            run:
+              web:
+                command: ["sh", "-c", "a b", "it's", ""]
+              worker:
+                command: [bin/worker, --queue=default, $QUEUE]
+        "#})
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            Some(&String::from(r"sh -c 'a b' 'it'\''s' ''")),
+            procfile.processes.get("web")
+        );
+        assert_eq!(
+            Some(&String::from("bin/worker --queue=default $QUEUE")),
+            procfile.processes.get("worker")
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::Procfile;
+use crate::{Formation, Procfile};
 use libcnb::data::launch::{Launch, Process, ProcessType, WorkingDirectory};
 use std::str::FromStr;
 
@@ -6,41 +6,76 @@ impl TryFrom<Procfile> for Launch {
     type Error = ProcfileConversionError;
 
     fn try_from(value: Procfile) -> Result<Self, Self::Error> {
+        value.into_launch(&Formation::default())
+    }
+}
+
+impl Procfile {
+    /// Converts the processes into CNB launch configuration, with the default process chosen
+    /// with the `app.json` formation, see [`Procfile::default_process_type_with`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a process name is not a valid CNB process type.
+    pub fn into_launch(self, formation: &Formation) -> Result<Launch, ProcfileConversionError> {
         let mut launch = Launch {
             labels: vec![],
             processes: vec![],
             slices: vec![],
         };
 
-        for (key, value) in value.processes {
+        let default = self
+            .default_process_type_with(formation)
+            .map(ToString::to_string);
+        for (key, value) in self.processes {
             launch.processes.push(Process {
                 r#type: ProcessType::from_str(&key)
                     .map_err(ProcfileConversionError::InvalidProcessType)?,
                 command: vec![String::from("bash"), String::from("-c")],
                 args: vec![value],
-                default: key == "web",
+                default: default.as_deref() == Some(key.as_str()),
                 working_directory: WorkingDirectory::App,
             });
         }
 
-        if launch.processes.len() == 1
-            && let Some(process) = launch.processes.first_mut()
-        {
-            process.default = true;
-        }
-
         Ok(launch)
     }
+
+    /// Returns the process type that becomes the default process without an `app.json`
+    ///
+    /// The `web` process is the default. Without it, a single process is the default.
+    #[must_use]
+    pub fn default_process_type(&self) -> Option<&str> {
+        self.default_process_type_with(&Formation::default())
+    }
+
+    /// Returns the process type that becomes the default process
+    ///
+    /// Like [`Procfile::default_process_type`], but with multiple processes and no `web`
+    /// process, the first one scaled up by the `app.json` formation is the default.
+    #[must_use]
+    pub fn default_process_type_with(&self, formation: &Formation) -> Option<&str> {
+        if self.processes.contains_key("web") {
+            Some("web")
+        } else if self.processes.len() == 1 {
+            self.processes.keys().next().map(String::as_str)
+        } else {
+            self.processes
+                .keys()
+                .find(|process_type| formation.process_types.contains(process_type))
+                .map(String::as_str)
+        }
+    }
 }
 
 #[derive(Debug)]
-pub(crate) enum ProcfileConversionError {
+pub enum ProcfileConversionError {
     InvalidProcessType(libcnb::data::launch::ProcessTypeError),
 }
 
 #[cfg(test)]
 mod test {
-    use crate::Procfile;
+    use crate::{Formation, Procfile};
     use libcnb::data::launch::{Launch, Process, WorkingDirectory};
     use libcnb::data::process_type;
 
@@ -140,6 +175,28 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_formation_default_process() {
+        let mut procfile = Procfile::new();
+        procfile.insert("foo", "foo_command");
+        procfile.insert("bar", "bar_command");
+        let formation = Formation {
+            process_types: vec![String::from("bar")],
+        };
+
+        assert_eq!(None, procfile.default_process_type());
+        let launch = procfile.into_launch(&formation).unwrap();
+
+        assert_eq!(
+            launch
+                .processes
+                .iter()
+                .map(|process| process.default)
+                .collect::<Vec<_>>(),
+            vec![false, true]
+        );
+    }
+
     #[test]
     fn test_no_processes() {
         let procfile = Procfile::new();
@@ -0,0 +1,42 @@
+//! Parsing and conversion logic for the `Procfile` format
+//!
+//! The buildpack binary (`src/main.rs`) and the `procfile` command-line tool of the
+//! `procfile-tools` crate are thin wrappers around this library.
+mod app_json;
+mod buildpack;
+mod diagnostic;
+mod diff;
+mod document;
+mod error;
+mod export;
+mod heroku_yml;
+mod launch;
+mod lint;
+mod merge;
+mod procfile;
+mod report;
+
+pub use crate::app_json::{AppJsonError, Formation};
+pub use crate::buildpack::{
+    apply_app_json, parse_procfile, procfile_launch, procfile_messages, read_procfile,
+};
+pub use crate::diagnostic::{
+    BUILDPACK_RULES, ProcfileDiagnostic, ProcfileFix, RULES, Rule, Severity, SourcePosition,
+    procfile_diagnostics,
+};
+pub use crate::diff::{DefaultProcessChange, ProcfileChange, ProcfileDiff};
+pub use crate::document::{ProcfileDocument, ProcfileEditError};
+pub use crate::error::{ProcfileBuildpackError, error_handler, print_error};
+pub use crate::export::{
+    ComposeOptions, ExportError, ExportedFile, KubernetesOptions, SystemdOptions, compose_file,
+    kubernetes_manifests, process_ports, systemd_units,
+};
+pub use crate::heroku_yml::HerokuYmlError;
+pub use crate::launch::ProcfileConversionError;
+pub use crate::lint::{LINTS, Lint, LintFinding, ProcfileEntry, lint_procfile};
+pub use crate::merge::{MergePolicy, MergedProcfile, ProcfileMergeError};
+pub use crate::procfile::{Procfile, ProcfileError, ProcfileParseError, ProcfileWarning};
+pub use crate::report::{BUILD_REPORT_FILE, BuildReport};
+
+// Dependencies only used by the buildpack binary.
+use libherokubuildpack as _;
@@ -0,0 +1,300 @@
+//! Contains the lint rules that check the entries of a `Procfile`
+//!
+//! Each [`Lint`] pairs a [`Rule`] with a check over all entries of a Procfile. Findings are
+//! turned into [`ProcfileDiagnostic`]s with the default severity of their rule, unless the entry
+//! suppresses the rule with a `# procfile-lint: allow(P002)` comment on the line above or at the
+//! end of its line. Rules are referenced by ID or name, separated by commas.
+mod background;
+mod dev_server;
+mod exec;
+mod localhost;
+mod port;
+mod secrets;
+mod spec;
+
+use crate::diagnostic::{
+    BACKGROUND_PROCESS, CORRECTED_KEY, DEV_SERVER, DUPLICATE_KEY, EMPTY_PROCFILE, HARDCODED_SECRET,
+    INVALID_PROCFILE, LOCALHOST_BIND, MISSING_EXEC, WEB_PORT,
+};
+use crate::procfile::parse_entries;
+use crate::{ProcfileDiagnostic, ProcfileError, ProcfileFix, Rule};
+use std::ops::Range;
+
+pub(crate) use spec::buildpack_warnings;
+
+/// A check that reports problems of a [`Rule`]
+#[derive(Debug)]
+pub struct Lint {
+    pub rule: &'static Rule,
+    /// Checks all entries of a Procfile, in order
+    pub check: fn(&[ProcfileEntry]) -> Vec<LintFinding>,
+}
+
+/// A `key: command` entry of a Procfile, located in the input
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProcfileEntry {
+    /// The key as written, including leading spaces
+    pub key: String,
+    /// The key corrected like the buildpack does, the CNB process type
+    pub process_type: String,
+    pub command: String,
+    /// Byte range of the key as written
+    pub key_span: Range<usize>,
+    /// Byte range of the command
+    pub command_span: Range<usize>,
+    /// Rule IDs and names suppressed by `procfile-lint` comments
+    pub(crate) allowed: Vec<String>,
+}
+
+/// A problem found by a lint
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LintFinding {
+    /// Byte range of the offending input
+    pub span: Range<usize>,
+    /// Description, which may contain ANSI styling
+    pub message: String,
+    pub fix: Option<ProcfileFix>,
+}
+
+impl LintFinding {
+    pub(crate) fn new(span: Range<usize>, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+            fix: None,
+        }
+    }
+}
+
+impl ProcfileEntry {
+    /// Returns true when a `procfile-lint` comment suppresses the rule for this entry
+    #[must_use]
+    pub fn allows(&self, rule: &Rule) -> bool {
+        self.allowed
+            .iter()
+            .any(|allowed| allowed == rule.id || allowed == rule.name)
+    }
+
+    /// Whitespace separated words of the command with their byte range in the input
+    ///
+    /// Shell quoting is not taken into account.
+    pub(crate) fn words(&self) -> impl Iterator<Item = (Range<usize>, &str)> {
+        let start = self.command_span.start;
+        self.command.split_whitespace().map(move |word| {
+            let offset = word.as_ptr() as usize - self.command.as_ptr() as usize;
+            (start + offset..start + offset + word.len(), word)
+        })
+    }
+
+    /// Values of the given flags with their byte range in the input
+    ///
+    /// Values follow the flag as the next word or after `=`, like `--port 3000` or
+    /// `--port=3000`. Values of single character flags can also be attached, like `-p3000`.
+    pub(crate) fn flag_values(&self, flags: &[&str]) -> Vec<(Range<usize>, &str)> {
+        let words = self.words().collect::<Vec<_>>();
+        words
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (span, word))| {
+                if flags.contains(word) {
+                    return words.get(index + 1).cloned();
+                }
+                flags.iter().find_map(|flag| {
+                    let value = word.strip_prefix(flag)?;
+                    let value = value.strip_prefix('=').or_else(|| {
+                        (flag.len() == 2 && !flag.starts_with("--") && !value.is_empty())
+                            .then_some(value)
+                    })?;
+                    Some((span.end - value.len()..span.end, value))
+                })
+            })
+            .collect()
+    }
+}
+
+/// All lints, ordered by rule ID
+pub static LINTS: &[Lint] = &[
+    Lint {
+        rule: &CORRECTED_KEY,
+        check: spec::corrected_key,
+    },
+    Lint {
+        rule: &DUPLICATE_KEY,
+        check: spec::duplicate_key,
+    },
+    Lint {
+        rule: &EMPTY_PROCFILE,
+        check: spec::empty_procfile,
+    },
+    Lint {
+        rule: &WEB_PORT,
+        check: port::web_port,
+    },
+    Lint {
+        rule: &LOCALHOST_BIND,
+        check: localhost::localhost_bind,
+    },
+    Lint {
+        rule: &DEV_SERVER,
+        check: dev_server::dev_server,
+    },
+    Lint {
+        rule: &HARDCODED_SECRET,
+        check: secrets::hardcoded_secret,
+    },
+    Lint {
+        rule: &BACKGROUND_PROCESS,
+        check: background::background_process,
+    },
+    Lint {
+        rule: &MISSING_EXEC,
+        check: exec::missing_exec,
+    },
+];
+
+/// Checks the input with the given lints, see [`LINTS`]
+///
+/// An invalid Procfile returns a single error and is not checked further. Diagnostics are
+/// ordered by their position in the input.
+#[must_use]
+pub fn lint_procfile(input: &str, lints: &[Lint]) -> Vec<ProcfileDiagnostic> {
+    let entries = match parse_entries(input) {
+        Ok(entries) => with_suppressions(input, entries),
+        Err(ProcfileError::ParseError(error)) => {
+            return vec![ProcfileDiagnostic::new(
+                input,
+                &INVALID_PROCFILE,
+                error.message(),
+                error.span(),
+            )];
+        }
+    };
+    let mut diagnostics = Vec::new();
+    for lint in lints {
+        for finding in (lint.check)(&entries) {
+            let suppressed = entries.iter().any(|entry| {
+                entry.key_span.start <= finding.span.start
+                    && finding.span.start <= entry.command_span.end
+                    && entry.allows(lint.rule)
+            });
+            if !suppressed {
+                let mut diagnostic =
+                    ProcfileDiagnostic::new(input, lint.rule, &finding.message, finding.span);
+                diagnostic.fix = finding.fix;
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    diagnostics
+}
+
+/// Locates the entries of a valid Procfile and their suppression comments
+#[cfg(test)]
+pub(crate) fn procfile_entries(input: &str) -> Vec<ProcfileEntry> {
+    with_suppressions(input, parse_entries(input).unwrap_or_default())
+}
+
+/// Adds the rules of the comment lines directly above each entry and at the end of its line
+fn with_suppressions(input: &str, mut entries: Vec<ProcfileEntry>) -> Vec<ProcfileEntry> {
+    for entry in &mut entries {
+        let comments = input[..entry.key_span.start]
+            .lines()
+            .rev()
+            .take_while(|line| line.trim_start().starts_with('#'));
+        entry.allowed = comments
+            .flat_map(allowed_rules)
+            .chain(allowed_rules(&entry.command))
+            .collect();
+    }
+    entries
+}
+
+/// Rules of the `# procfile-lint: allow(...)` comments in the text
+fn allowed_rules(text: &str) -> Vec<String> {
+    text.match_indices('#')
+        .filter_map(|(index, _)| {
+            text[index + 1..]
+                .trim_start()
+                .strip_prefix("procfile-lint:")?
+                .trim_start()
+                .strip_prefix("allow(")?
+                .split_once(')')
+                .map(|(rules, _)| rules)
+        })
+        .flat_map(|rules| rules.split(','))
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_spans() {
+        let input = "# web\r\n  Web:   rails s\r\nworker: sidekiq";
+        let entries = procfile_entries(input);
+
+        assert_eq!(2, entries.len());
+        assert_eq!("  Web", &input[entries[0].key_span.clone()]);
+        assert_eq!("web", entries[0].process_type);
+        assert_eq!("rails s", &input[entries[0].command_span.clone()]);
+        assert_eq!("sidekiq", &input[entries[1].command_span.clone()]);
+    }
+
+    #[test]
+    fn suppression_comments() {
+        let entries = procfile_entries(
+            "# procfile-lint: allow(P002, duplicate-key)\nWeb: a\nweb: b # procfile-lint: allow(P003)\n\n# procfile-lint: allow(P002)\n\nWorker: c\n",
+        );
+
+        assert!(entries[0].allows(&CORRECTED_KEY));
+        assert!(entries[0].allows(&DUPLICATE_KEY));
+        assert!(!entries[1].allows(&CORRECTED_KEY));
+        assert!(entries[1].allows(&DUPLICATE_KEY));
+        // A blank line separates the comment from the entry
+        assert!(!entries[2].allows(&CORRECTED_KEY));
+    }
+
+    #[test]
+    fn suppressed_diagnostics() {
+        let diagnostics = lint_procfile(
+            "# procfile-lint: allow(corrected-key)\nWeb: a $PORT\nWorker: b\n",
+            LINTS,
+        );
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(3, diagnostics[0].start.line);
+    }
+
+    #[test]
+    fn custom_lints() {
+        static RULE: Rule = Rule {
+            id: "X001",
+            name: "no-sleep",
+            description: "Commands must not sleep",
+            severity: crate::Severity::Error,
+        };
+        static CUSTOM: &[Lint] = &[Lint {
+            rule: &RULE,
+            check: |entries| {
+                entries
+                    .iter()
+                    .filter(|entry| entry.command.starts_with("sleep"))
+                    .map(|entry| LintFinding::new(entry.command_span.clone(), "Sleeping"))
+                    .collect()
+            },
+        }];
+
+        let diagnostics = lint_procfile("Web: sleep 1\n", CUSTOM);
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!("X001", diagnostics[0].rule.id);
+        assert_eq!(crate::Severity::Error, diagnostics[0].severity);
+        assert_eq!(5..12, diagnostics[0].span);
+    }
+}
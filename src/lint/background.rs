@@ -0,0 +1,119 @@
+//! Lint for commands that move the process into the background
+use crate::lint::{LintFinding, ProcfileEntry};
+use bullet_stream::style;
+
+/// Commands that detach the command they run from the process
+const DETACHING_COMMANDS: [&str; 4] = ["nohup", "setsid", "disown", "daemonize"];
+
+/// Flags that make common servers fork into the background, like `unicorn -D` or
+/// `gunicorn --daemon`
+const DAEMON_FLAGS: [&str; 4] = ["--daemon", "--daemonize", "--detach", "-D"];
+
+/// Values that keep a daemon flag in the foreground, like `httpd -D FOREGROUND` or
+/// `redis-server --daemonize no`
+const FOREGROUND_VALUES: [&str; 4] = ["FOREGROUND", "no", "false", "off"];
+
+const FOREGROUND: &str = "The process has to stay in the foreground: the container stops when the command exits, and signals like SIGTERM on shutdown only reach the foreground process.";
+
+pub(super) fn background_process(entries: &[ProcfileEntry]) -> Vec<LintFinding> {
+    entries
+        .iter()
+        .flat_map(|entry| {
+            let words = entry.words().collect::<Vec<_>>();
+            let mut findings = Vec::new();
+            for (index, (span, word)) in words.iter().enumerate() {
+                let program = word.rsplit('/').next().unwrap_or(word);
+                let flag = word.split_once('=').map_or(*word, |(flag, _)| flag);
+                let value = word
+                    .split_once('=')
+                    .map(|(_, value)| value)
+                    .or_else(|| words.get(index + 1).map(|(_, value)| *value));
+
+                let description = if DETACHING_COMMANDS.contains(&program) {
+                    format!(
+                        "{} detaches the command from the process.",
+                        style::value(program)
+                    )
+                } else if DAEMON_FLAGS.contains(&flag)
+                    && !value.is_some_and(|value| FOREGROUND_VALUES.contains(&value))
+                {
+                    format!(
+                        "{} runs the server as a daemon in the background.",
+                        style::value(flag)
+                    )
+                } else {
+                    continue;
+                };
+                findings.push(LintFinding::new(
+                    span.clone(),
+                    format!("{description} {FOREGROUND}"),
+                ));
+            }
+
+            // A trailing `&` backgrounds the command and the shell exits right away
+            let command = entry.command.trim_end();
+            if command.ends_with('&') && !command.ends_with("&&") {
+                let end = entry.command_span.start + command.len();
+                findings.push(LintFinding::new(
+                    end - 1..end,
+                    format!(
+                        "A trailing {} runs the command in the background. {FOREGROUND}",
+                        style::value("&")
+                    ),
+                ));
+            }
+            findings
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::procfile_entries;
+    use bullet_stream::strip_ansi;
+
+    fn backgrounded(input: &str) -> Vec<&str> {
+        background_process(&procfile_entries(input))
+            .into_iter()
+            .map(|finding| &input[finding.span])
+            .collect()
+    }
+
+    #[test]
+    fn background_commands() {
+        assert_eq!(vec!["&"], backgrounded("worker: sidekiq &\n"));
+        assert_eq!(vec!["&"], backgrounded("worker: sidekiq&  \n"));
+        assert_eq!(
+            vec!["nohup"],
+            backgrounded("worker: nohup python worker.py\n")
+        );
+        assert_eq!(
+            vec!["/usr/bin/setsid"],
+            backgrounded("worker: /usr/bin/setsid ./worker\n")
+        );
+        assert_eq!(vec!["-D"], backgrounded("web: unicorn -D -p $PORT\n"));
+        assert_eq!(
+            vec!["--daemon", "--daemonize=yes"],
+            backgrounded("web: gunicorn --daemon app:app\nredis: redis-server --daemonize=yes\n")
+        );
+    }
+
+    #[test]
+    fn foreground_commands() {
+        assert!(backgrounded("worker: bundle exec sidekiq\n").is_empty());
+        assert!(backgrounded("web: ./migrate && ./server\n").is_empty());
+        assert!(backgrounded("web: httpd -D FOREGROUND\n").is_empty());
+        assert!(backgrounded("redis: redis-server --daemonize no\n").is_empty());
+    }
+
+    #[test]
+    fn message_explains_foreground_requirement() {
+        let finding = background_process(&procfile_entries("worker: sidekiq &\n")).remove(0);
+
+        assert_eq!(
+            "A trailing `&` runs the command in the background. The process has to stay in the foreground: the container stops when the command exits, and signals like SIGTERM on shutdown only reach the foreground process.",
+            strip_ansi(finding.message)
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Lint for development servers and modes used as processes
+use crate::lint::{LintFinding, ProcfileEntry};
+use bullet_stream::style;
+
+/// Consecutive command words that start a development server or mode
+///
+/// A pattern word ending with `*` matches words starting with the rest of it. Words also match
+/// executables in a directory, so `nodemon` matches `node_modules/.bin/nodemon`.
+struct DevServer {
+    words: &'static [&'static str],
+    reason: &'static str,
+}
+
+const PYTHON: &[DevServer] = &[
+    DevServer {
+        words: &["manage.py", "runserver"],
+        reason: "The Django development server has not gone through security audits or performance tests. Use a WSGI server like `gunicorn`.",
+    },
+    DevServer {
+        words: &["flask", "run"],
+        reason: "The Flask development server is not designed to be efficient, stable or secure. Use a WSGI server like `gunicorn`.",
+    },
+    DevServer {
+        words: &["--reload"],
+        reason: "Reloading watches the file system and restarts on changes, which only slows down an immutable image.",
+    },
+];
+
+const DEV_SCRIPT: &str = "The `dev` script usually starts a server with file watching and unoptimized builds. Build the app and run the `start` script.";
+const WEBPACK_DEV_SERVER: &str = "The webpack development server serves unoptimized bundles from memory. Serve the built assets instead.";
+
+const NODE: &[DevServer] = &[
+    DevServer {
+        words: &["npm", "run", "dev"],
+        reason: DEV_SCRIPT,
+    },
+    DevServer {
+        words: &["yarn", "dev"],
+        reason: DEV_SCRIPT,
+    },
+    DevServer {
+        words: &["yarn", "run", "dev"],
+        reason: DEV_SCRIPT,
+    },
+    DevServer {
+        words: &["pnpm", "dev"],
+        reason: DEV_SCRIPT,
+    },
+    DevServer {
+        words: &["pnpm", "run", "dev"],
+        reason: DEV_SCRIPT,
+    },
+    DevServer {
+        words: &["nodemon"],
+        reason: "`nodemon` restarts the process on file changes and adds a supervisor that does not forward signals reliably. Run `node` directly.",
+    },
+    DevServer {
+        words: &["webpack-dev-server"],
+        reason: WEBPACK_DEV_SERVER,
+    },
+    DevServer {
+        words: &["webpack", "serve"],
+        reason: WEBPACK_DEV_SERVER,
+    },
+    DevServer {
+        words: &["next", "dev"],
+        reason: "The Next.js development server compiles pages on demand without optimizations. Use `next build` and `next start`.",
+    },
+    DevServer {
+        words: &["ng", "serve"],
+        reason: "The Angular development server is not meant for production. Build the app and serve the output.",
+    },
+];
+
+const DEVELOPMENT_ENVIRONMENT: &str = "The development environment reloads code, shows detailed error pages and skips production settings.";
+
+const RUBY: &[DevServer] = &[
+    DevServer {
+        words: &["-e", "development"],
+        reason: DEVELOPMENT_ENVIRONMENT,
+    },
+    DevServer {
+        words: &["--environment", "development"],
+        reason: DEVELOPMENT_ENVIRONMENT,
+    },
+    DevServer {
+        words: &["--environment=development"],
+        reason: DEVELOPMENT_ENVIRONMENT,
+    },
+    DevServer {
+        words: &["RAILS_ENV=development"],
+        reason: DEVELOPMENT_ENVIRONMENT,
+    },
+    DevServer {
+        words: &["RACK_ENV=development"],
+        reason: DEVELOPMENT_ENVIRONMENT,
+    },
+];
+
+const PHP: &[DevServer] = &[DevServer {
+    words: &["php", "-S*"],
+    reason: "The PHP built-in web server runs a single process and is meant for development only. Use a web server like Apache or Nginx with PHP-FPM.",
+}];
+
+const GO: &[DevServer] = &[DevServer {
+    words: &["go", "run"],
+    reason: "`go run` compiles the program on every start and needs the Go toolchain at runtime. Build a binary and run it.",
+}];
+
+/// Development servers and modes, by ecosystem
+const ECOSYSTEMS: &[&[DevServer]] = &[PYTHON, NODE, RUBY, PHP, GO];
+
+pub(super) fn dev_server(entries: &[ProcfileEntry]) -> Vec<LintFinding> {
+    entries
+        .iter()
+        .flat_map(|entry| {
+            let words = entry.words().collect::<Vec<_>>();
+            ECOSYSTEMS
+                .iter()
+                .flat_map(|dev_servers| dev_servers.iter())
+                .filter_map(move |dev_server| {
+                    let matched = words.windows(dev_server.words.len()).find(|window| {
+                        window
+                            .iter()
+                            .zip(dev_server.words)
+                            .all(|((_, word), pattern)| matches(word, pattern))
+                    })?;
+                    let span = matched[0].0.start..matched[matched.len() - 1].0.end;
+                    let offset = entry.command_span.start;
+                    let text = &entry.command[span.start - offset..span.end - offset];
+                    Some(LintFinding::new(
+                        span,
+                        format!(
+                            "{} is meant for development. {}",
+                            style::value(text),
+                            dev_server.reason
+                        ),
+                    ))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn matches(word: &str, pattern: &str) -> bool {
+    let word = word.rsplit('/').next().unwrap_or(word);
+    match pattern.strip_suffix('*') {
+        Some(prefix) => word.starts_with(prefix),
+        None => word == pattern,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::procfile_entries;
+    use bullet_stream::strip_ansi;
+
+    fn matched(input: &str) -> Vec<&str> {
+        dev_server(&procfile_entries(input))
+            .into_iter()
+            .map(|finding| &input[finding.span])
+            .collect()
+    }
+
+    #[test]
+    fn dev_servers() {
+        assert_eq!(
+            vec!["manage.py runserver"],
+            matched("web: python manage.py runserver 0.0.0.0:$PORT\n")
+        );
+        assert_eq!(
+            vec!["./manage.py runserver"],
+            matched("web: ./manage.py runserver 0.0.0.0:$PORT\n")
+        );
+        assert_eq!(vec!["flask run"], matched("web: flask run --port $PORT\n"));
+        assert_eq!(vec!["npm run dev"], matched("web: npm run dev\n"));
+        assert_eq!(
+            vec!["-e development"],
+            matched("web: rails s -e development\n")
+        );
+        assert_eq!(
+            vec!["node_modules/.bin/nodemon"],
+            matched("web: node_modules/.bin/nodemon server.js\n")
+        );
+        assert_eq!(
+            vec!["webpack-dev-server"],
+            matched("web: webpack-dev-server\n")
+        );
+        assert_eq!(
+            vec!["php -S0.0.0.0:$PORT"],
+            matched("web: php -S0.0.0.0:$PORT\n")
+        );
+        assert_eq!(
+            vec!["--reload", "RAILS_ENV=development"],
+            matched("web: uvicorn app:app --reload\nworker: RAILS_ENV=development sidekiq\n")
+        );
+    }
+
+    #[test]
+    fn production_servers() {
+        assert!(matched("web: gunicorn app:app\n").is_empty());
+        assert!(matched("web: npm start\nrelease: npm run build\n").is_empty());
+        assert!(matched("web: rails s -e production\n").is_empty());
+        assert!(matched("web: next start -p $PORT\n").is_empty());
+    }
+
+    #[test]
+    fn message_explains_why() {
+        let finding = dev_server(&procfile_entries("web: flask run\n")).remove(0);
+
+        assert_eq!(
+            "`flask run` is meant for development. The Flask development server is not designed to be efficient, stable or secure. Use a WSGI server like `gunicorn`.",
+            strip_ansi(finding.message)
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Lint for compound commands whose last command is not `exec`'d
+//!
+//! Every command is run with `bash -c`. For a compound command like `cd api && bundle exec puma`,
+//! bash stays the main process of the container and runs the server as its child, so signals
+//! like SIGTERM on shutdown never reach the server.
+use crate::ProcfileFix;
+use crate::lint::{LintFinding, ProcfileEntry};
+use bullet_stream::style;
+use std::ops::Range;
+
+/// Commands that are not replaced by `exec`, like shell builtins, or mark a shell construct the
+/// lint does not follow
+const SKIPPED_COMMANDS: [&str; 19] = [
+    "exec", "wait", "exit", "true", "false", "done", "fi", "esac", "source", ".", ":", "cd",
+    "export", "set", "unset", "shift", "trap", "ulimit", "umask",
+];
+
+pub(super) fn missing_exec(entries: &[ProcfileEntry]) -> Vec<LintFinding> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let last = last_command(&entry.command)?;
+            let text = &entry.command[last.clone()];
+            // Leading assignments apply to the command, `FOO=bar exec server` keeps them
+            let program = text
+                .split_whitespace()
+                .find(|word| !is_assignment(word))?;
+            let program_start = program.as_ptr() as usize - text.as_ptr() as usize;
+            if SKIPPED_COMMANDS.contains(&program) || is_grouped_or_piped(text, program_start) {
+                return None;
+            }
+
+            let offset = entry.command_span.start;
+            let insert = offset + last.start + program_start;
+            Some(LintFinding {
+                fix: Some(ProcfileFix {
+                    description: String::from("Insert `exec`"),
+                    span: insert..insert,
+                    replacement: String::from("exec "),
+                }),
+                ..LintFinding::new(
+                    offset + last.start..offset + last.end,
+                    format!(
+                        "The last command of a compound command is not run with {exec}. Bash stays the main process and runs {program} as its child, so SIGTERM on shutdown does not reach it. Prefix it with {exec} to replace bash.",
+                        exec = style::value("exec"),
+                        program = style::value(program),
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Byte range of the last command of a compound command, separated by `&&`, `||`, `;` or `&`
+///
+/// Returns `None` for simple commands, which bash already replaces itself with, for commands
+/// that end in a separator and for fallbacks after `||`, which only run when the command before
+/// them fails.
+fn last_command(command: &str) -> Option<Range<usize>> {
+    // End of the last separator and whether it is `||`
+    let mut last_separator = None;
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in command.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if quote == Some('\'') {
+            // Nothing is special in single quotes, not even `\`
+            if c == '\'' {
+                quote = None;
+            }
+        } else if c == '\\' {
+            escaped = true;
+        } else if let Some(open) = quote {
+            if c == open {
+                quote = None;
+            }
+        } else {
+            match c {
+                '\'' | '"' => quote = Some(c),
+                ';' => last_separator = Some((index + 1, false)),
+                // Not redirections like `2>&1` or `&>log`
+                '&' if !command[..index].ends_with(['>', '<'])
+                    && !command[index + 1..].starts_with('>') =>
+                {
+                    last_separator = Some((index + 1, false));
+                }
+                '|' if command[index + 1..].starts_with('|') => {
+                    last_separator = Some((index + 2, true));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let (start, fallback) = last_separator?;
+    if fallback {
+        return None;
+    }
+    let rest = &command[start..];
+    let trimmed = rest.trim_start();
+    (!trimmed.trim_end().is_empty()).then(|| {
+        let start = start + rest.len() - trimmed.len();
+        start..start + trimmed.trim_end().len()
+    })
+}
+
+/// Whether the command is part of a pipeline, or opens or closes a group or subshell like
+/// `{ ./a; ./b; }` or `(./a && ./b)`
+///
+/// Brackets of expansions like `${PORT}` or `$(nproc)` and arguments like the `{}` of `find -exec`
+/// are not groups.
+fn is_grouped_or_piped(command: &str, program_start: usize) -> bool {
+    let mut depth = 0_usize;
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in command.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if quote == Some('\'') {
+            if c == '\'' {
+                quote = None;
+            }
+        } else if c == '\\' {
+            escaped = true;
+        } else if let Some(open) = quote {
+            if c == open {
+                quote = None;
+            }
+        } else {
+            match c {
+                '\'' | '"' => quote = Some(c),
+                '|' => return true,
+                '(' | '{' if index == program_start => return true,
+                '(' | '{' => depth += 1,
+                ')' | '}' if depth == 0 => return true,
+                ')' | '}' => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+    false
+}
+
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::procfile_entries;
+    use bullet_stream::strip_ansi;
+
+    fn last_commands(input: &str) -> Vec<&str> {
+        missing_exec(&procfile_entries(input))
+            .into_iter()
+            .map(|finding| &input[finding.span])
+            .collect()
+    }
+
+    #[test]
+    fn compound_commands_without_exec() {
+        assert_eq!(
+            vec!["bundle exec puma -C config/puma.rb"],
+            last_commands("web: cd api && bundle exec puma -C config/puma.rb\n")
+        );
+        assert_eq!(
+            vec!["node server.js"],
+            last_commands("web: ./setup.sh; node server.js  \n")
+        );
+        assert_eq!(
+            vec!["node server.js 2>&1"],
+            last_commands("web: cd api && node server.js 2>&1\n")
+        );
+        assert_eq!(
+            vec!["./start"],
+            last_commands("web: echo 'a || b' && ./start\n")
+        );
+        assert_eq!(
+            vec!["bundle exec puma -p ${PORT}"],
+            last_commands("web: cd api && bundle exec puma -p ${PORT}\n")
+        );
+        assert_eq!(
+            vec!["puma -w $(nproc) -p $PORT"],
+            last_commands("web: ./setup; puma -w $(nproc) -p $PORT\n")
+        );
+        assert_eq!(
+            vec!["find . -name '*.tmp' -exec rm {} +"],
+            last_commands("worker: cd tmp && find . -name '*.tmp' -exec rm {} +\n")
+        );
+    }
+
+    #[test]
+    fn commands_without_missing_exec() {
+        assert!(last_commands("web: bundle exec puma -C config/puma.rb\n").is_empty());
+        assert!(last_commands("web: cd api && exec bundle exec puma\n").is_empty());
+        assert!(last_commands("web: cd api && FOO=bar exec node server.js\n").is_empty());
+        assert!(last_commands("web: echo 'starting; now' \"and && this\"\n").is_empty());
+        assert!(last_commands("worker: ./a & ./b & wait\n").is_empty());
+        assert!(last_commands("web: ./setup && ./server | tee log\n").is_empty());
+        assert!(last_commands("worker: sidekiq &\n").is_empty());
+        assert!(last_commands("web: node server.js 2>&1 &>log\n").is_empty());
+        assert!(last_commands("web: ./setup && { ./migrate; ./server; }\n").is_empty());
+        assert!(last_commands("web: ./setup && (cd api && ./server)\n").is_empty());
+        assert!(last_commands("web: ./build && source .env\n").is_empty());
+        assert!(last_commands("web: ./build && . ./env.sh\n").is_empty());
+        assert!(last_commands("web: ./build && cd api\n").is_empty());
+        assert!(last_commands("web: echo 'a && b' && ./start || ./fallback\n").is_empty());
+    }
+
+    #[test]
+    fn fix_inserts_exec_after_assignments() {
+        let input = "web: cd api && PORT_OFFSET=1 node server.js\n";
+        let finding = missing_exec(&procfile_entries(input)).remove(0);
+        let fix = finding.fix.unwrap();
+
+        let mut fixed = input.to_string();
+        fixed.replace_range(fix.span, &fix.replacement);
+        assert_eq!("web: cd api && PORT_OFFSET=1 exec node server.js\n", fixed);
+        assert_eq!(
+            "The last command of a compound command is not run with `exec`. Bash stays the main process and runs `node` as its child, so SIGTERM on shutdown does not reach it. Prefix it with `exec` to replace bash.",
+            strip_ansi(finding.message)
+        );
+    }
+}
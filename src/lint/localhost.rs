@@ -0,0 +1,132 @@
+//! Lint for `web` processes that only listen on the loopback interface
+use crate::ProcfileFix;
+use crate::lint::{LintFinding, ProcfileEntry};
+use bullet_stream::style;
+use std::net::IpAddr;
+use std::ops::Range;
+
+/// Flags that set the listen address of common servers
+///
+/// - `-b`/`--binding`: `rails server`, `puma`
+/// - `-b`/`--bind`: `gunicorn`, `hypercorn`, `python -m http.server`
+/// - `--host`: `uvicorn`, `flask run`, `vite`
+/// - `-H`/`--hostname`: `next start`
+/// - `-S`: `php -S`
+const BIND_FLAGS: [&str; 7] = [
+    "-b",
+    "--bind",
+    "--binding",
+    "--host",
+    "-H",
+    "--hostname",
+    "-S",
+];
+
+/// The router connects to the container's interface, not its loopback interface
+pub(super) fn localhost_bind(entries: &[ProcfileEntry]) -> Vec<LintFinding> {
+    entries
+        .iter()
+        .filter(|entry| entry.process_type == "web")
+        .flat_map(|entry| entry.flag_values(&BIND_FLAGS))
+        .filter_map(|(span, value)| {
+            let host = loopback_host(value)?;
+            let span = span.start + host.start..span.start + host.end;
+            Some(LintFinding {
+                fix: Some(ProcfileFix {
+                    description: String::from("Bind to `0.0.0.0`"),
+                    span: span.clone(),
+                    replacement: String::from("0.0.0.0"),
+                }),
+                ..LintFinding::new(
+                    span,
+                    format!(
+                        "The {web} process only listens on {host}, which cannot be reached from outside the container. Bind to {all} to accept requests from the platform.",
+                        web = style::value("web"),
+                        host = style::value(&value[host]),
+                        all = style::value("0.0.0.0"),
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Byte range of a loopback host in an address like `127.0.0.1`, `localhost:$PORT` or
+/// `tcp://[::1]:3000`
+fn loopback_host(value: &str) -> Option<Range<usize>> {
+    let start = value.find("://").map_or(0, |index| index + 3);
+    let address = &value[start..];
+    if let Ok(ip) = address.parse::<IpAddr>() {
+        return ip.is_loopback().then_some(start..value.len());
+    }
+
+    let end = if address.starts_with('[') {
+        address.find(']')? + 1
+    } else {
+        address.find([':', '/']).unwrap_or(address.len())
+    };
+    let host = address[..end].trim_start_matches('[').trim_end_matches(']');
+    let loopback = host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback());
+    loopback.then_some(start..start + end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::procfile_entries;
+    use bullet_stream::strip_ansi;
+
+    fn hosts(input: &str) -> Vec<&str> {
+        localhost_bind(&procfile_entries(input))
+            .into_iter()
+            .map(|finding| &input[finding.span])
+            .collect()
+    }
+
+    #[test]
+    fn localhost_binds() {
+        assert_eq!(
+            vec!["127.0.0.1"],
+            hosts("web: rails s -b 127.0.0.1 -p $PORT\n")
+        );
+        assert_eq!(
+            vec!["localhost"],
+            hosts("web: gunicorn --bind localhost:$PORT app:app\n")
+        );
+        assert_eq!(
+            vec!["127.0.0.1"],
+            hosts("web: uvicorn app:app --host=127.0.0.1 --port $PORT\n")
+        );
+        assert_eq!(vec!["[::1]"], hosts("web: puma -b tcp://[::1]:$PORT\n"));
+        assert_eq!(vec!["::1"], hosts("web: next start -H ::1 -p $PORT\n"));
+        assert_eq!(vec!["127.0.0.2"], hosts("web: php -S127.0.0.2:$PORT\n"));
+    }
+
+    #[test]
+    fn reachable_binds() {
+        assert!(hosts("web: rails s -b 0.0.0.0 -p $PORT\n").is_empty());
+        assert!(hosts("web: gunicorn --bind :$PORT app:app\n").is_empty());
+        assert!(hosts("web: puma -b tcp://[::]:$PORT\n").is_empty());
+        assert!(hosts("web: puma -b unix:///tmp/puma.sock\n").is_empty());
+        assert!(hosts("worker: gunicorn --bind localhost:9000 app:app\n").is_empty());
+    }
+
+    #[test]
+    fn message_and_fix() {
+        let finding = localhost_bind(&procfile_entries("web: rails s -b localhost\n")).remove(0);
+
+        assert_eq!(
+            "The `web` process only listens on `localhost`, which cannot be reached from outside the container. Bind to `0.0.0.0` to accept requests from the platform.",
+            strip_ansi(finding.message)
+        );
+        assert_eq!(
+            Some(ProcfileFix {
+                description: String::from("Bind to `0.0.0.0`"),
+                span: 16..25,
+                replacement: String::from("0.0.0.0"),
+            }),
+            finding.fix
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Lint for `web` processes that do not listen on `$PORT`
+use crate::ProcfileFix;
+use crate::lint::{LintFinding, ProcfileEntry};
+use bullet_stream::style;
+use std::ops::Range;
+
+/// Flags that set the port of common servers, like `rails s -p 3000` or `uvicorn --port 8000`
+const PORT_FLAGS: [&str; 3] = ["-p", "--port", "-port"];
+
+/// The platform routes requests and health checks to `$PORT`, so `web` has to listen on it
+pub(super) fn web_port(entries: &[ProcfileEntry]) -> Vec<LintFinding> {
+    entries
+        .iter()
+        .filter(|entry| entry.process_type == "web")
+        .flat_map(|entry| {
+            let literals = port_literals(entry);
+            if literals.is_empty() && !entry.command.contains("PORT") {
+                vec![LintFinding::new(
+                    entry.command_span.clone(),
+                    format!(
+                        "The {web} process never references {port}. The platform routes requests and health checks to {port}, so the process has to listen on it.",
+                        web = style::value("web"),
+                        port = style::value("$PORT"),
+                    ),
+                )]
+            } else {
+                literals
+                    .into_iter()
+                    .map(|(span, port)| LintFinding {
+                        fix: Some(ProcfileFix {
+                            description: String::from("Use `$PORT`"),
+                            span: span.clone(),
+                            replacement: String::from("$PORT"),
+                        }),
+                        ..LintFinding::new(
+                            span,
+                            format!(
+                                "Port {} is hardcoded. The {} process has to listen on {}, which is set by the platform.",
+                                style::value(port),
+                                style::value("web"),
+                                style::value("$PORT"),
+                            ),
+                        )
+                    })
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+/// Numeric values of port flags and `PORT` assignments, as `-p 3000`, `--port=3000`, `-p3000` or
+/// `PORT=3000`
+fn port_literals(entry: &ProcfileEntry) -> Vec<(Range<usize>, &str)> {
+    let assignments = entry.words().filter_map(|(span, word)| {
+        let value = word.strip_prefix("PORT=")?;
+        Some((span.end - value.len()..span.end, value))
+    });
+    let mut literals = entry
+        .flag_values(&PORT_FLAGS)
+        .into_iter()
+        .chain(assignments)
+        .filter(|(_, value)| !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit()))
+        .collect::<Vec<_>>();
+    literals.sort_by_key(|(span, _)| span.start);
+    literals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::procfile_entries;
+    use bullet_stream::strip_ansi;
+
+    fn findings(input: &str) -> Vec<(String, String)> {
+        web_port(&procfile_entries(input))
+            .into_iter()
+            .map(|finding| (input[finding.span].to_string(), strip_ansi(finding.message)))
+            .collect()
+    }
+
+    #[test]
+    fn web_without_port() {
+        assert_eq!(
+            vec![(
+                String::from("node server.js"),
+                String::from(
+                    "The `web` process never references `$PORT`. The platform routes requests and health checks to `$PORT`, so the process has to listen on it."
+                )
+            )],
+            findings("web: node server.js\nworker: node worker.js\n")
+        );
+    }
+
+    #[test]
+    fn web_with_port() {
+        assert!(findings("web: bundle exec rails s -p $PORT\n").is_empty());
+        assert!(findings("web: gunicorn --bind 0.0.0.0:${PORT} app:app\n").is_empty());
+        assert!(findings("web: rails s -p $PORT\nworker: rails s -p 3000\n").is_empty());
+        // An empty value is not a port
+        assert!(findings("web: puma --port= -e $PORT\n").is_empty());
+    }
+
+    #[test]
+    fn hardcoded_ports() {
+        assert_eq!(
+            vec![
+                String::from("3000"),
+                String::from("8080"),
+                String::from("9000"),
+            ],
+            findings("web: rails s -p 3000 & uvicorn --port=8080 & puma -p9000 -e $PORT\n")
+                .into_iter()
+                .map(|(span, _)| span)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![String::from("3000")],
+            findings("web: PORT=3000 node server.js\n")
+                .into_iter()
+                .map(|(span, _)| span)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "Port `3000` is hardcoded. The `web` process has to listen on `$PORT`, which is set by the platform.",
+            findings("web: rails s -p 3000\n")[0].1
+        );
+    }
+
+    #[test]
+    fn hardcoded_port_fix() {
+        let finding = web_port(&procfile_entries("web: rails s --port 3000\n")).remove(0);
+
+        assert_eq!(
+            Some(ProcfileFix {
+                description: String::from("Use `$PORT`"),
+                span: 20..24,
+                replacement: String::from("$PORT"),
+            }),
+            finding.fix
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Contains the warnings the buildpack prints while parsing a `Procfile`
+//!
+//! They are only found here, located in the input: the parser, the build report and the lints of
+//! their rules all use [`buildpack_warnings`], so they cannot disagree.
+use crate::lint::{LintFinding, ProcfileEntry};
+use crate::{ProcfileFix, ProcfileWarning};
+use std::ops::Range;
+
+/// The buildpack's warnings in the order it prints them, with the byte range they are about
+///
+/// `procfile-lint` comments do not suppress them, the buildpack always prints its warnings.
+pub(crate) fn buildpack_warnings(
+    entries: &[ProcfileEntry],
+) -> Vec<(Range<usize>, ProcfileWarning)> {
+    let corrected = entries
+        .iter()
+        .filter(|entry| entry.key != entry.process_type)
+        .map(|entry| {
+            (
+                entry.key_span.clone(),
+                ProcfileWarning::KeyCorrected {
+                    original: entry.key.clone(),
+                    fixed: entry.process_type.clone(),
+                },
+            )
+        });
+    // Every definition but the last of a process type is overridden
+    let duplicates = entries.iter().enumerate().filter_map(|(index, entry)| {
+        let last = entries[index + 1..]
+            .iter()
+            .rfind(|other| other.process_type == entry.process_type)?;
+        Some((
+            entry.key_span.clone(),
+            ProcfileWarning::DuplicateKey {
+                key: entry.process_type.clone(),
+                value: last.command.clone(),
+            },
+        ))
+    });
+    let empty = entries
+        .is_empty()
+        .then_some((0..0, ProcfileWarning::EmptyFile));
+
+    corrected.chain(duplicates).chain(empty).collect()
+}
+
+/// Keys that are accepted, but corrected to a valid process type
+pub(super) fn corrected_key(entries: &[ProcfileEntry]) -> Vec<LintFinding> {
+    buildpack_warnings(entries)
+        .into_iter()
+        .filter_map(|(span, warning)| {
+            let ProcfileWarning::KeyCorrected { original, fixed } = &warning else {
+                return None;
+            };
+            Some(LintFinding {
+                fix: Some(ProcfileFix {
+                    description: format!("Rename `{}` to `{fixed}`", original.trim_start()),
+                    span: span.clone(),
+                    replacement: fixed.clone(),
+                }),
+                ..LintFinding::new(span, warning.to_string())
+            })
+        })
+        .collect()
+}
+
+pub(super) fn duplicate_key(entries: &[ProcfileEntry]) -> Vec<LintFinding> {
+    findings(entries, |warning| {
+        matches!(warning, ProcfileWarning::DuplicateKey { .. })
+    })
+}
+
+pub(super) fn empty_procfile(entries: &[ProcfileEntry]) -> Vec<LintFinding> {
+    findings(entries, |warning| *warning == ProcfileWarning::EmptyFile)
+}
+
+fn findings(
+    entries: &[ProcfileEntry],
+    filter: impl Fn(&ProcfileWarning) -> bool,
+) -> Vec<LintFinding> {
+    buildpack_warnings(entries)
+        .into_iter()
+        .filter(|(_, warning)| filter(warning))
+        .map(|(span, warning)| LintFinding::new(span, warning.to_string()))
+        .collect()
+}
@@ -1,17 +1,30 @@
-mod error;
-mod launch;
-mod procfile;
-
-use crate::error::{ProcfileBuildpackError, error_handler};
-use crate::procfile::Procfile;
+use bullet_stream::state::Bullet;
 use bullet_stream::{Print, style};
 use libcnb::build::{BuildContext, BuildResult, BuildResultBuilder};
+use libcnb::data::launch::Launch;
+use libcnb::data::layer_name;
 use libcnb::detect::{DetectContext, DetectResult, DetectResultBuilder};
 use libcnb::generic::{GenericMetadata, GenericPlatform};
+use libcnb::layer::UncachedLayerDefinition;
 use libcnb::{Buildpack, buildpack_main};
-use std::io::stdout;
+use procfile_buildpack::{
+    BUILD_REPORT_FILE, BuildReport, HerokuYmlError, Procfile, ProcfileBuildpackError,
+    apply_app_json, error_handler, parse_procfile, procfile_launch, procfile_messages,
+};
+use std::io::{Stdout, stdout};
 use std::path::Path;
 
+// Dependencies only used by the library.
+use annotate_snippets as _;
+use indoc as _;
+use linked_hash_map as _;
+#[cfg(feature = "serde")]
+use serde as _;
+use serde_json as _;
+use sha2 as _;
+use winnow as _;
+use yaml_rust2 as _;
+
 #[cfg(test)]
 use libcnb_test as _;
 
@@ -23,7 +36,7 @@ impl Buildpack for ProcfileBuildpack {
     type Error = ProcfileBuildpackError;
 
     fn detect(&self, context: DetectContext<Self>) -> libcnb::Result<DetectResult, Self::Error> {
-        if dir_has_procfile(context.app_dir) {
+        if dir_has_procfile(&context.app_dir) || dir_has_heroku_yml_run_section(&context.app_dir) {
             DetectResultBuilder::pass().build()
         } else {
             DetectResultBuilder::fail().build()
@@ -31,35 +44,12 @@ impl Buildpack for ProcfileBuildpack {
     }
 
     fn build(&self, context: BuildContext<Self>) -> libcnb::Result<BuildResult, Self::Error> {
-        let mut bullet = Print::new(stdout())
-            .h2("Procfile Buildpack")
-            .bullet(format!("Processes from {}", style::value("Procfile")));
-
-        let procfile: Procfile = fs_err::read_to_string(context.app_dir.join("Procfile"))
-            .map_err(ProcfileBuildpackError::CannotReadProcfileContents)
-            .and_then(|procfile_contents| {
-                procfile_contents
-                    .parse()
-                    .map_err(ProcfileBuildpackError::ProcfileParsingError)
-            })?;
-
-        let warning_prefix = style::important("WARNING:");
-        for message in &procfile.warnings {
-            bullet = bullet.sub_bullet(format!("{warning_prefix} {message}"));
-        }
-
-        for (name, command) in &procfile.processes {
-            bullet = bullet.sub_bullet(format!("{name}: {cmd}", cmd = style::command(command)));
-        }
-        bullet.done().done();
+        let mut report = BuildReport::default();
+        let output = Print::new(stdout()).h2("Procfile Buildpack");
+        let (launch, output) = build_launch(&context.app_dir, &mut report, output)?;
+        write_report(&context, &report, output).done();
 
-        BuildResultBuilder::new()
-            .launch(
-                procfile
-                    .try_into()
-                    .map_err(ProcfileBuildpackError::ProcfileConversionError)?,
-            )
-            .build()
+        BuildResultBuilder::new().launch(launch).build()
     }
 
     fn on_error(&self, error: libcnb::Error<Self::Error>) {
@@ -67,10 +57,90 @@ impl Buildpack for ProcfileBuildpack {
     }
 }
 
+/// Reads the processes from the `Procfile`, or the `heroku.yml` `run` section without one
+fn build_launch(
+    app_dir: &Path,
+    report: &mut BuildReport,
+    output: Print<Bullet<Stdout>>,
+) -> Result<(Launch, Print<Bullet<Stdout>>), ProcfileBuildpackError> {
+    let source = if dir_has_procfile(app_dir) {
+        "Procfile"
+    } else {
+        "heroku.yml"
+    };
+    let mut bullet = output.bullet(format!("Processes from {}", style::value(source)));
+
+    let path = app_dir.join(source);
+    let mut procfile = if source == "Procfile" {
+        // Read once, so the digest is of the parsed contents
+        let contents = fs_err::read_to_string(path)
+            .map_err(ProcfileBuildpackError::CannotReadProcfileContents)?;
+        report.source(&contents);
+        parse_procfile(&contents)?
+    } else {
+        let contents = fs_err::read_to_string(path)
+            .map_err(ProcfileBuildpackError::CannotReadHerokuYmlContents)?;
+        report.heroku_yml_source(&contents);
+        Procfile::from_heroku_yml(&contents)
+            .and_then(|procfile| procfile.ok_or(HerokuYmlError::InvalidRunSection))
+            .map_err(ProcfileBuildpackError::HerokuYmlParsingError)?
+    };
+    let formation = apply_app_json(app_dir, &mut procfile);
+    report.procfile(&procfile, &formation);
+
+    for message in procfile_messages(&procfile) {
+        bullet = bullet.sub_bullet(message);
+    }
+
+    Ok((procfile_launch(procfile, &formation)?, bullet.done()))
+}
+
+/// Writes the report to a launch layer, so it is kept in the image for platforms to read
+///
+/// The report is informational, failing to write it does not fail the build. Failed builds are
+/// not exported, so they have no report.
+fn write_report(
+    context: &BuildContext<ProcfileBuildpack>,
+    report: &BuildReport,
+    output: Print<Bullet<Stdout>>,
+) -> Print<Bullet<Stdout>> {
+    let written = context
+        .uncached_layer(
+            layer_name!("report"),
+            UncachedLayerDefinition {
+                build: false,
+                launch: true,
+            },
+        )
+        .map_err(|error| error.to_string())
+        .and_then(|layer| {
+            fs_err::write(
+                layer.path().join(BUILD_REPORT_FILE),
+                format!("{:#}", report.to_json()),
+            )
+            .map_err(|error| error.to_string())
+        });
+
+    match written {
+        Ok(()) => output,
+        Err(error) => output.warning(format!("Cannot write build report\n\n{error}")),
+    }
+}
+
 fn dir_has_procfile(app_dir: impl AsRef<Path>) -> bool {
     app_dir.as_ref().join("Procfile").exists()
 }
 
+/// Invalid `run` sections pass detection, so the build can explain the problem
+fn dir_has_heroku_yml_run_section(app_dir: impl AsRef<Path>) -> bool {
+    fs_err::read_to_string(app_dir.as_ref().join("heroku.yml")).is_ok_and(|contents| {
+        !matches!(
+            Procfile::from_heroku_yml(&contents),
+            Ok(None) | Err(HerokuYmlError::InvalidYaml(_))
+        )
+    })
+}
+
 // Implements the main function and wires up the framework for the given buildpack.
 buildpack_main!(ProcfileBuildpack);
 
@@ -88,6 +158,14 @@ mod tests {
     #[test]
     fn test_missing_procfile_detect() {
         let app_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/missing_procfile");
-        assert!(!dir_has_procfile(app_dir));
+        assert!(!dir_has_procfile(&app_dir));
+        assert!(!dir_has_heroku_yml_run_section(app_dir));
+    }
+
+    #[test]
+    fn test_heroku_yml_run_section_detect() {
+        let app_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/heroku_yml_run");
+        assert!(!dir_has_procfile(&app_dir));
+        assert!(dir_has_heroku_yml_run_section(app_dir));
     }
 }
@@ -0,0 +1,231 @@
+//! Contains logic for layering multiple `Procfile`s on top of each other
+use crate::procfile::collect_entries;
+use crate::{Procfile, ProcfileWarning};
+use linked_hash_map::LinkedHashMap;
+use std::fmt::Display;
+
+/// How to resolve a key that is defined in more than one merged Procfile
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MergePolicy {
+    /// The value from the later Procfile is used, like a duplicate key within a single Procfile
+    Override,
+    /// The value from the earlier Procfile is used
+    KeepFirst,
+    /// Merging fails
+    ErrorOnConflict,
+}
+
+/// The result of [`Procfile::merge`]
+#[derive(Debug, Eq, PartialEq)]
+pub struct MergedProcfile {
+    pub procfile: Procfile,
+    /// Name of the Procfile each process was taken from, in process order
+    pub sources: LinkedHashMap<String, String>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum ProcfileMergeError {
+    Conflict {
+        key: String,
+        first: String,
+        second: String,
+    },
+}
+
+impl Display for ProcfileMergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcfileMergeError::Conflict { key, first, second } => {
+                write!(f, "Key `{key}` is defined in both `{first}` and `{second}`")
+            }
+        }
+    }
+}
+
+impl Procfile {
+    /// Merges named Procfiles in order of increasing precedence
+    ///
+    /// Warnings of each source are kept and attributed to the source they came from.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the first key defined more than once when using
+    /// [`MergePolicy::ErrorOnConflict`].
+    pub fn merge<'a>(
+        sources: impl IntoIterator<Item = (&'a str, &'a Procfile)>,
+        policy: MergePolicy,
+    ) -> Result<MergedProcfile, ProcfileMergeError> {
+        let mut warnings = Vec::new();
+        let mut entries = Vec::new();
+
+        for (source, procfile) in sources {
+            warnings.extend(
+                procfile
+                    .warnings
+                    .iter()
+                    .filter(|warning| **warning != ProcfileWarning::EmptyFile)
+                    .map(|warning| ProcfileWarning::InSource {
+                        source: source.to_string(),
+                        warning: Box::new(warning.clone()),
+                    }),
+            );
+            entries.extend(
+                procfile
+                    .processes
+                    .iter()
+                    .map(|(key, value)| (key.clone(), (source, value.clone()))),
+            );
+        }
+
+        let mut conflict = None;
+        let merged = collect_entries(entries, |key, (first, _), (second, _)| {
+            let (used, ignored) = match policy {
+                MergePolicy::Override => (second, first),
+                MergePolicy::KeepFirst => (first, second),
+                MergePolicy::ErrorOnConflict => {
+                    conflict.get_or_insert_with(|| ProcfileMergeError::Conflict {
+                        key: key.to_string(),
+                        first: (*first).to_string(),
+                        second: (*second).to_string(),
+                    });
+                    (first, second)
+                }
+            };
+            warnings.push(ProcfileWarning::MergedDuplicateKey {
+                key: key.to_string(),
+                used: (*used).to_string(),
+                ignored: (*ignored).to_string(),
+            });
+            policy == MergePolicy::Override
+        });
+
+        if let Some(conflict) = conflict {
+            return Err(conflict);
+        }
+
+        let mut processes = LinkedHashMap::new();
+        let mut sources = LinkedHashMap::new();
+        for (key, (source, value)) in merged {
+            sources.insert(key.clone(), source.to_string());
+            processes.insert(key, value);
+        }
+
+        if processes.is_empty() {
+            warnings.push(ProcfileWarning::EmptyFile);
+        }
+
+        Ok(MergedProcfile {
+            procfile: Procfile {
+                processes,
+                warnings,
+            },
+            sources,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bullet_stream::strip_ansi;
+
+    fn merge(policy: MergePolicy) -> Result<MergedProcfile, ProcfileMergeError> {
+        let base: Procfile = "web: bin/start\nRELEASE: bin/release\nworker: bin/worker"
+            .parse()
+            .unwrap();
+        let app: Procfile = "web: bundle exec puma\nclock: bin/clock".parse().unwrap();
+
+        Procfile::merge([("Procfile.base", &base), ("Procfile", &app)], policy)
+    }
+
+    #[test]
+    fn merge_override() {
+        let merged = merge(MergePolicy::Override).unwrap();
+
+        assert_eq!(
+            vec![
+                ("release", "bin/release"),
+                ("worker", "bin/worker"),
+                ("web", "bundle exec puma"),
+                ("clock", "bin/clock"),
+            ],
+            merged
+                .procfile
+                .processes
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                ("release", "Procfile.base"),
+                ("worker", "Procfile.base"),
+                ("web", "Procfile"),
+                ("clock", "Procfile"),
+            ],
+            merged
+                .sources
+                .iter()
+                .map(|(key, source)| (key.as_str(), source.as_str()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                "In `Procfile.base`: Procfile key `RELEASE` has been corrected to `release`. Please update your Procfile.",
+                "Duplicate key `web` found in `Procfile.base` and `Procfile`. The value from `Procfile` will be used.",
+            ],
+            merged
+                .procfile
+                .warnings
+                .iter()
+                .map(|warning| strip_ansi(warning.to_string()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn merge_keep_first() {
+        let merged = merge(MergePolicy::KeepFirst).unwrap();
+
+        assert_eq!(
+            vec!["web", "release", "worker", "clock"],
+            merged
+                .procfile
+                .processes
+                .keys()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("bin/start", merged.procfile.processes["web"]);
+        assert_eq!("Procfile.base", merged.sources["web"]);
+        assert_eq!(
+            Some(&ProcfileWarning::MergedDuplicateKey {
+                key: "web".to_string(),
+                used: "Procfile.base".to_string(),
+                ignored: "Procfile".to_string(),
+            }),
+            merged.procfile.warnings.last()
+        );
+    }
+
+    #[test]
+    fn merge_error_on_conflict() {
+        assert_eq!(
+            Err(ProcfileMergeError::Conflict {
+                key: "web".to_string(),
+                first: "Procfile.base".to_string(),
+                second: "Procfile".to_string(),
+            }),
+            merge(MergePolicy::ErrorOnConflict)
+        );
+    }
+
+    #[test]
+    fn merge_empty_procfiles() {
+        let empty: Procfile = "".parse().unwrap();
+        let merged =
+            Procfile::merge([("a", &empty), ("b", &empty)], MergePolicy::Override).unwrap();
+
+        assert_eq!(vec![ProcfileWarning::EmptyFile], merged.procfile.warnings);
+    }
+}
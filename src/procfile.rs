@@ -1,23 +1,36 @@
 //! Contains logic for parsing the `Procfile` format
+use crate::lint::{ProcfileEntry, buildpack_warnings};
 use bullet_stream::style;
 use linked_hash_map::LinkedHashMap;
 use std::fmt::Display;
 use winnow::{
     Parser,
     ascii::{line_ending, space0, till_line_ending},
-    combinator::{alt, eof, opt, preceded, repeat, repeat_till, terminated, trace},
+    combinator::{alt, eof, opt, peek, preceded, repeat, repeat_till, terminated, trace},
     error::{ContextError, ParseError, StrContext, StrContextValue},
     stream::{Offset, Stream},
     token::{one_of, take_while},
 };
 
 #[derive(Debug, Eq, PartialEq)]
-pub(crate) struct Procfile {
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "SerializedProcfile")
+)]
+pub struct Procfile {
+    /// Only set by parsing, so every key is strictly valid and every value fits on one line
     pub(crate) processes: LinkedHashMap<String, String>,
-    pub(crate) warnings: Vec<String>,
+    pub warnings: Vec<ProcfileWarning>,
 }
 
 impl Procfile {
+    /// The processes by type, in order
+    #[must_use]
+    pub fn processes(&self) -> &LinkedHashMap<String, String> {
+        &self.processes
+    }
+
     #[cfg(test)]
     pub(crate) fn new() -> Self {
         Self {
@@ -32,8 +45,69 @@ impl Procfile {
     }
 }
 
+/// A non-fatal problem found while parsing a `Procfile`
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum ProcfileWarning {
+    /// A semi-valid key was transformed into a strictly valid key
+    KeyCorrected { original: String, fixed: String },
+    /// A key was defined more than once, the last value takes precedence
+    DuplicateKey { key: String, value: String },
+    /// No processes are defined
+    EmptyFile,
+    /// A key was defined in more than one merged Procfile
+    MergedDuplicateKey {
+        key: String,
+        used: String,
+        ignored: String,
+    },
+    /// A warning from one of the merged Procfiles
+    InSource {
+        source: String,
+        warning: Box<ProcfileWarning>,
+    },
+    /// A process type in the `app.json` formation is not defined
+    UnknownFormationProcessType { process_type: String },
+    /// The `app.json` cannot be read or has an invalid formation, so it is ignored
+    InvalidAppJson { error: String },
+}
+
+impl Display for ProcfileWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcfileWarning::KeyCorrected { original, fixed } => write!(
+                f,
+                "Procfile key {} has been corrected to {}. Please update your Procfile.",
+                style::value(original),
+                style::value(fixed)
+            ),
+            ProcfileWarning::DuplicateKey { key, value } => write!(
+                f,
+                "Duplicate key `{key}` found. The value `{value}` will be used."
+            ),
+            ProcfileWarning::EmptyFile => write!(f, "Empty file, no processes defined"),
+            ProcfileWarning::MergedDuplicateKey { key, used, ignored } => write!(
+                f,
+                "Duplicate key `{key}` found in `{ignored}` and `{used}`. The value from `{used}` will be used."
+            ),
+            ProcfileWarning::InSource { source, warning } => write!(f, "In `{source}`: {warning}"),
+            ProcfileWarning::UnknownFormationProcessType { process_type } => write!(
+                f,
+                "Process type {} in the app.json formation is not defined and will not run.",
+                style::value(process_type)
+            ),
+            ProcfileWarning::InvalidAppJson { error } => write!(
+                f,
+                "The app.json is ignored, its formation cannot be used to choose the default process. {error}"
+            ),
+        }
+    }
+}
+
 #[derive(Debug)]
-pub(crate) enum ProcfileError {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProcfileError {
     ParseError(ProcfileParseError),
 }
 
@@ -45,8 +119,16 @@ impl Display for ProcfileError {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub(crate) struct ProcfileParseError {
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "SerializedProcfileParseError",
+        try_from = "SerializedProcfileParseError"
+    )
+)]
+pub struct ProcfileParseError {
     message: String,
     span: std::ops::Range<usize>,
     input: String,
@@ -69,19 +151,139 @@ impl ProcfileParseError {
             input,
         }
     }
+
+    /// Description of what went wrong
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Byte range of the offending input
+    #[must_use]
+    pub fn span(&self) -> std::ops::Range<usize> {
+        self.span.clone()
+    }
+
+    /// One-based line number where the error starts
+    #[must_use]
+    pub fn line(&self) -> usize {
+        line_column(&self.input, self.span.start).0
+    }
+
+    /// One-based column (in characters) where the error starts
+    #[must_use]
+    pub fn column(&self) -> usize {
+        line_column(&self.input, self.span.start).1
+    }
+}
+
+/// Wire format of a [`Procfile`]
+///
+/// Processes are validated on deserialization, like they are when parsing.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SerializedProcfile {
+    processes: LinkedHashMap<String, String>,
+    warnings: Vec<ProcfileWarning>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SerializedProcfile> for Procfile {
+    type Error = String;
+
+    fn try_from(value: SerializedProcfile) -> Result<Self, Self::Error> {
+        let SerializedProcfile {
+            processes,
+            warnings,
+        } = value;
+
+        if let Some(key) = processes.keys().find(|key| !is_valid_key(key)) {
+            Err(format!("process type `{key}` is not a valid Procfile key"))
+        } else if let Some(key) = processes
+            .iter()
+            .find_map(|(key, value)| (!is_valid_value(value)).then_some(key))
+        {
+            Err(format!(
+                "command of process type `{key}` is empty, has leading spaces or line breaks"
+            ))
+        } else {
+            Ok(Self {
+                processes,
+                warnings,
+            })
+        }
+    }
+}
+
+/// Wire format of a [`ProcfileParseError`]
+///
+/// Line and column are derived from the span on serialization and ignored on deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SerializedProcfileParseError {
+    message: String,
+    span: std::ops::Range<usize>,
+    #[serde(default)]
+    line: usize,
+    #[serde(default)]
+    column: usize,
+    input: String,
+}
+
+#[cfg(feature = "serde")]
+impl From<ProcfileParseError> for SerializedProcfileParseError {
+    fn from(error: ProcfileParseError) -> Self {
+        Self {
+            line: error.line(),
+            column: error.column(),
+            message: error.message,
+            span: error.span,
+            input: error.input,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SerializedProcfileParseError> for ProcfileParseError {
+    type Error = String;
+
+    fn try_from(value: SerializedProcfileParseError) -> Result<Self, Self::Error> {
+        let SerializedProcfileParseError {
+            message,
+            span,
+            input,
+            ..
+        } = value;
+
+        if span.start <= span.end && input.get(span.start..span.end).is_some() {
+            Ok(Self {
+                message,
+                span,
+                input,
+            })
+        } else {
+            Err(format!(
+                "span {}..{} is not a valid range of the input",
+                span.start, span.end
+            ))
+        }
+    }
 }
 
 impl std::str::FromStr for Procfile {
     type Err = ProcfileError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let (processes, mut warnings) = parse_procfile
-            .parse(input)
-            .map_err(|e| ProcfileError::ParseError(ProcfileParseError::from_parse(&e, input)))?;
-
-        if processes.is_empty() {
-            warnings.push("Empty file, no processes defined".to_string());
-        }
+        let entries = parse_entries(input)?;
+        let warnings = buildpack_warnings(&entries)
+            .into_iter()
+            .map(|(_, warning)| warning)
+            .collect();
+        // Inserting a duplicate key moves it to the end, like the buildpack always did
+        let processes = entries
+            .into_iter()
+            .map(|entry| (entry.process_type, entry.command))
+            .collect();
 
         Ok(Procfile {
             processes,
@@ -90,6 +292,20 @@ impl std::str::FromStr for Procfile {
     }
 }
 
+/// Writes the processes as a canonical Procfile
+///
+/// Each process is written as `key: value` on its own line, in order. Comments, blank lines and
+/// warnings are not included. Processes can only be set by parsing, so the output always parses
+/// back to the same processes without any key warnings.
+impl Display for Procfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (key, value) in &self.processes {
+            writeln!(f, "{key}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
 impl std::fmt::Display for ProcfileParseError {
     fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use annotate_snippets::{AnnotationKind, Level, Renderer, Snippet};
@@ -103,53 +319,106 @@ impl std::fmt::Display for ProcfileParseError {
     }
 }
 
-/// Returns a mapping of key/values and warnings from a Procfile
-fn parse_procfile(
-    input: &mut &str,
-) -> winnow::Result<(LinkedHashMap<String, String>, Vec<String>)> {
-    let mut warnings: Vec<String> = Vec::new();
-    let mut key_values: Vec<(String, String)> = Vec::new();
-    let mut out = LinkedHashMap::new();
+/// Returns the located entries of a Procfile, in order
+///
+/// Both the processes and the warnings are derived from these entries, see [`buildpack_warnings`].
+pub(crate) fn parse_entries(input: &str) -> Result<Vec<ProcfileEntry>, ProcfileError> {
+    parse_procfile
+        .parse(input)
+        .map_err(|e| ProcfileError::ParseError(ProcfileParseError::from_parse(&e, input)))
+}
+
+/// Returns the entries of a Procfile with their byte range in the input
+fn parse_procfile(input: &mut &str) -> winnow::Result<Vec<ProcfileEntry>> {
+    let start = *input;
+    let mut entries = Vec::new();
 
     while !input.is_empty() {
         opt(parse_ignored_lines).parse_next(input)?;
 
+        let key_start = input.offset_from(&start);
         let checkpoint = input.checkpoint();
         // Strict path
-        if let Ok(kv) = parse_key_value(input) {
-            key_values.push(kv);
-        } else {
-            input.reset(&checkpoint);
-            match parse_permissive_key_fixed(input) {
-                Ok((original, fixed)) => {
-                    let value = parse_value.parse_next(input)?;
-
-                    warnings.push(format!(
-                        "Procfile key {} has been corrected to {}. Please update your Procfile.",
-                        style::value(&original),
-                        style::value(&fixed)
-                    ));
-                    key_values.push((fixed, value));
-                }
-                Err(err) => {
-                    return Err(err);
-                }
-            }
-        }
+        let (original, fixed) =
+            if let Ok(key) = terminated(parse_key, peek(parse_value)).parse_next(input) {
+                (key.clone(), key)
+            } else {
+                input.reset(&checkpoint);
+                parse_permissive_key_fixed(input)?
+            };
+        let leading = input.len() - input.trim_start_matches([' ', '\t']).len();
+        let command_start = input.offset_from(&start) + leading;
+        let command = parse_value.parse_next(input)?;
+
+        entries.push(ProcfileEntry {
+            key_span: key_start..key_start + original.len(),
+            command_span: command_start..command_start + command.len(),
+            key: original,
+            process_type: fixed,
+            command,
+            allowed: Vec::new(),
+        });
 
         opt(parse_ignored_lines).parse_next(input)?;
     }
 
-    for (key, value) in key_values {
-        if out.contains_key(&key) {
-            warnings.push(format!(
-                "Duplicate key `{key}` found. The value `{value}` will be used."
-            ));
+    Ok(entries)
+}
+
+/// Collects entries into a map, keeping the order of the last insertion of each key
+///
+/// For a duplicate key `on_duplicate` is called with the key, the existing value and the new value
+/// and returns whether the new value replaces the existing one.
+pub(crate) fn collect_entries<V>(
+    entries: impl IntoIterator<Item = (String, V)>,
+    mut on_duplicate: impl FnMut(&str, &V, &V) -> bool,
+) -> LinkedHashMap<String, V> {
+    let mut out = LinkedHashMap::new();
+    for (key, value) in entries {
+        if let Some(previous) = out.get(&key)
+            && !on_duplicate(&key, previous, &value)
+        {
+            continue;
         }
         out.insert(key, value);
     }
+    out
+}
+
+/// Returns `true` when the key is strictly valid
+pub(crate) fn is_valid_key(key: &str) -> bool {
+    parse_key.parse(format!("{key}:").as_str()).is_ok()
+}
+
+/// Returns `true` when the value is parsed back unchanged after a key
+#[cfg(feature = "serde")]
+fn is_valid_value(value: &str) -> bool {
+    parse_value.parse(value).is_ok_and(|parsed| parsed == value)
+}
+
+/// Returns the key of a single `key: value` line, corrected if it is semi-valid
+///
+/// Comments, empty lines and invalid lines return `None`.
+pub(crate) fn parse_line_key(line: &str) -> Option<String> {
+    let mut input = line;
+    parse_key_value(&mut input)
+        .map(|(key, _)| key)
+        .or_else(|_| {
+            let mut input = line;
+            parse_permissive_key_fixed(&mut input)
+                .and_then(|(_, fixed)| parse_value(&mut input).map(|_| fixed))
+        })
+        .ok()
+}
 
-    Ok((out, warnings))
+/// One-based line and column (in characters) of a byte offset in the input
+pub(crate) fn line_column(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
 }
 
 /// Extracts and transforms a semi-valid key or returns an error
@@ -325,7 +594,7 @@ mod tests {
         let input = "IamAvalidKeyButNotStrictly: echo 'done'";
         let result: Procfile = input.parse().unwrap();
         assert_eq!(1, result.warnings.len());
-        assert_eq!("Procfile key `IamAvalidKeyButNotStrictly` has been corrected to `iamavalidkeybutnotstrictly`. Please update your Procfile.".to_string(),  strip_ansi(result.warnings.last().unwrap()));
+        assert_eq!("Procfile key `IamAvalidKeyButNotStrictly` has been corrected to `iamavalidkeybutnotstrictly`. Please update your Procfile.".to_string(),  strip_ansi(result.warnings.last().unwrap().to_string()));
         assert_eq!(
             "echo 'done'",
             result.processes.get("iamavalidkeybutnotstrictly").unwrap()
@@ -411,7 +680,7 @@ mod tests {
         let procfile = "".parse::<Procfile>().unwrap();
         assert_eq!(0, procfile.processes.len());
         assert_eq!(
-            &"Empty file, no processes defined".to_string(),
+            &ProcfileWarning::EmptyFile,
             procfile.warnings.first().unwrap()
         );
         assert_eq!(1, procfile.warnings.len());
@@ -492,6 +761,95 @@ mod tests {
         }
     }
 
+    #[test]
+    fn display_writes_canonical_procfile() {
+        let input = "# Comment\n   Web_Server:   bundle exec puma -p $PORT\nworker: rake jobs:work\n\nworker: bundle exec sidekiq\nrelease:echo 'done'  \n";
+        let procfile: Procfile = input.parse().unwrap();
+
+        assert_eq!(
+            "web-server: bundle exec puma -p $PORT\nworker: bundle exec sidekiq\nrelease: echo 'done'  \n",
+            procfile.to_string()
+        );
+
+        let reparsed: Procfile = procfile.to_string().parse().unwrap();
+        assert_eq!(procfile.processes, reparsed.processes);
+        assert!(reparsed.warnings.is_empty());
+    }
+
+    #[test]
+    fn display_empty_procfile() {
+        let procfile = Procfile::new();
+        assert_eq!("", procfile.to_string());
+    }
+
+    #[test]
+    fn parse_error_reports_line_and_column() {
+        let input = "web: rails s\nis_w.e.b: echo hello";
+        let Err(ProcfileError::ParseError(error)) = input.parse::<Procfile>() else {
+            panic!("Expected a parse error");
+        };
+
+        assert_eq!(17..18, error.span());
+        assert_eq!(2, error.line());
+        assert_eq!(5, error.column());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn procfile_serde_round_trip() {
+        let procfile: Procfile = "web: rails s\nWORKER: rake jobs:work".parse().unwrap();
+        let json = serde_json::to_value(&procfile).unwrap();
+
+        assert_eq!(
+            serde_json::json!({
+                "processes": {
+                    "web": "rails s",
+                    "worker": "rake jobs:work"
+                },
+                "warnings": [
+                    { "kind": "key_corrected", "original": "WORKER", "fixed": "worker" }
+                ]
+            }),
+            json
+        );
+        assert_eq!(procfile, serde_json::from_value(json).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn procfile_deserialize_validates_processes() {
+        for processes in [
+            serde_json::json!({ "Web_Server": "rails s" }),
+            serde_json::json!({ "web": "rails s\nworker: sidekiq" }),
+            serde_json::json!({ "web": " rails s" }),
+            serde_json::json!({ "web": "" }),
+        ] {
+            assert!(
+                serde_json::from_value::<Procfile>(
+                    serde_json::json!({ "processes": processes, "warnings": [] })
+                )
+                .is_err(),
+                "{processes}"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn parse_error_serde_round_trip() {
+        let input = "web: rails s\nis_w.e.b: echo hello";
+        let Err(ProcfileError::ParseError(error)) = input.parse::<Procfile>() else {
+            panic!("Expected a parse error");
+        };
+        let json = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(json["message"], error.message());
+        assert_eq!(json["span"], serde_json::json!({ "start": 17, "end": 18 }));
+        assert_eq!(json["line"], 2);
+        assert_eq!(json["column"], 5);
+        assert_eq!(error, serde_json::from_value(json).unwrap());
+    }
+
     #[test]
     fn max_length_key_is_63_chars() {
         let input = r"
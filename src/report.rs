@@ -0,0 +1,203 @@
+//! Contains the machine-readable report of a build
+use crate::diagnostic::warning_rule;
+use crate::lint::buildpack_warnings;
+use crate::procfile::{line_column, parse_entries};
+use crate::{Formation, Procfile, ProcfileWarning};
+use bullet_stream::strip_ansi;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+/// Name of the report file, written to the buildpack's `report` launch layer
+pub const BUILD_REPORT_FILE: &str = "build-report.json";
+
+/// Summary of a build for platforms, alongside the human readable output
+///
+/// Warnings are identified by stable rule IDs, so consumers do not need to match on message
+/// wording. Only successful builds have a report, a failed build is not exported.
+#[derive(Debug, Default)]
+pub struct BuildReport {
+    file: Option<&'static str>,
+    digest: Option<String>,
+    warnings: Vec<Value>,
+    /// Warnings were located in the Procfile source and are not taken from the parsed Procfile
+    located_warnings: bool,
+    processes: Vec<Value>,
+    default_process: Option<String>,
+}
+
+impl BuildReport {
+    /// Records the digest of the Procfile contents and the buildpack's warnings found in them
+    ///
+    /// The warnings are the ones the buildpack prints, `procfile-lint` comments do not suppress
+    /// them.
+    pub fn source(&mut self, contents: &str) {
+        self.file = Some("Procfile");
+        self.digest = Some(digest(contents));
+        self.located_warnings = true;
+        let Ok(entries) = parse_entries(contents) else {
+            return;
+        };
+        self.warnings = buildpack_warnings(&entries)
+            .into_iter()
+            .map(|(span, warning)| {
+                let rule = warning_rule(&warning);
+                let (line, column) = line_column(contents, span.start);
+                json!({
+                    "rule": rule.id,
+                    "name": rule.name,
+                    "message": strip_ansi(warning.to_string()),
+                    "line": line,
+                    "column": column,
+                })
+            })
+            .collect();
+    }
+
+    /// Records the digest of the `heroku.yml` the processes are read from
+    pub fn heroku_yml_source(&mut self, contents: &str) {
+        self.file = Some("heroku.yml");
+        self.digest = Some(digest(contents));
+    }
+
+    /// Records the processes and the default process of a parsed Procfile
+    ///
+    /// Warnings that were not located in the Procfile source are recorded without a line.
+    pub fn procfile(&mut self, procfile: &Procfile, formation: &Formation) {
+        let unlocated = procfile
+            .warnings
+            .iter()
+            .filter(|warning| {
+                !self.located_warnings
+                    || matches!(
+                        warning,
+                        ProcfileWarning::UnknownFormationProcessType { .. }
+                            | ProcfileWarning::InvalidAppJson { .. }
+                    )
+            })
+            .map(|warning| {
+                let rule = warning_rule(warning);
+                json!({
+                    "rule": rule.id,
+                    "name": rule.name,
+                    "message": strip_ansi(warning.to_string()),
+                })
+            })
+            .collect::<Vec<_>>();
+        self.warnings.extend(unlocated);
+        self.processes = procfile
+            .processes
+            .iter()
+            .map(|(process_type, command)| json!({ "type": process_type, "command": command }))
+            .collect();
+        self.default_process = procfile
+            .default_process_type_with(formation)
+            .map(ToString::to_string);
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "procfile": self
+                .digest
+                .as_ref()
+                .map(|digest| json!({ "file": self.file, "digest": digest })),
+            "processes": self.processes,
+            "default_process": self.default_process,
+            "warnings": self.warnings,
+        })
+    }
+}
+
+fn digest(contents: &str) -> String {
+    format!("sha256:{:x}", Sha256::digest(contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn successful_build_report() {
+        let contents = "web: rails s\nWorker: sidekiq\n";
+        let mut report = BuildReport::default();
+        report.source(contents);
+        report.procfile(&contents.parse().unwrap(), &Formation::default());
+
+        assert_eq!(
+            json!({
+                "procfile": {
+                    "file": "Procfile",
+                    "digest": "sha256:fe76c07e8afda292e94ace4014755258babc53a75bf12af2bfd91c961c943767",
+                },
+                "processes": [
+                    { "type": "web", "command": "rails s" },
+                    { "type": "worker", "command": "sidekiq" },
+                ],
+                "default_process": "web",
+                "warnings": [{
+                    "rule": "P002",
+                    "name": "corrected-key",
+                    "message": "Procfile key `Worker` has been corrected to `worker`. Please update your Procfile.",
+                    "line": 2,
+                    "column": 1,
+                }],
+            }),
+            report.to_json()
+        );
+    }
+
+    #[test]
+    fn suppressed_warnings_are_reported() {
+        let contents = "# procfile-lint: allow(P002)\nWorker: sidekiq\n";
+        let mut report = BuildReport::default();
+        report.source(contents);
+        let procfile: Procfile = contents.parse().unwrap();
+        report.procfile(&procfile, &Formation::default());
+        let json = report.to_json();
+
+        assert_eq!(1, procfile.warnings.len());
+        assert_eq!("P002", json["warnings"][0]["rule"]);
+        assert_eq!(2, json["warnings"][0]["line"]);
+    }
+
+    #[test]
+    fn heroku_yml_build_report() {
+        let mut report = BuildReport::default();
+        report.heroku_yml_source("run:\n  Web: rails s\n");
+        report.procfile(
+            &Procfile::from_heroku_yml("run:\n  Web: rails s\n")
+                .unwrap()
+                .unwrap(),
+            &Formation::default(),
+        );
+        let json = report.to_json();
+
+        assert_eq!("heroku.yml", json["procfile"]["file"]);
+        assert_eq!("web", json["default_process"]);
+        assert_eq!(
+            json!([{
+                "rule": "P002",
+                "name": "corrected-key",
+                "message": "Procfile key `Web` has been corrected to `web`. Please update your Procfile.",
+            }]),
+            json["warnings"]
+        );
+    }
+
+    #[test]
+    fn formation_build_report() {
+        let contents = "worker: sidekiq\nclock: clockwork\n";
+        let mut report = BuildReport::default();
+        report.source(contents);
+        let mut procfile: Procfile = contents.parse().unwrap();
+        let formation = procfile
+            .apply_app_json(r#"{"formation": {"clock": {}, "release": {}}}"#)
+            .unwrap();
+        report.procfile(&procfile, &formation);
+        let json = report.to_json();
+
+        assert_eq!("clock", json["default_process"]);
+        assert_eq!("P005", json["warnings"][0]["rule"]);
+        assert_eq!(Value::Null, json["warnings"][0]["line"]);
+    }
+}